@@ -1,5 +1,9 @@
 use std::fs;
 
+#[path = "../shared/iters.rs"]
+mod iters;
+use iters::Iters;
+
 fn main() {
     let ints = parse_input("./1/input");
     let increases = count_increases(ints);
@@ -8,8 +12,9 @@ fn main() {
 
 fn count_increases(readings: Vec<i32> ) -> usize
 {
-    return readings.windows(2)
-        .filter(|pair| pair[1] > pair[0])
+    return readings.into_iter()
+        .pairwise()
+        .filter(|(prev, next)| next > prev)
         .count();
 }
 
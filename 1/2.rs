@@ -1,5 +1,9 @@
 use std::fs;
 
+#[path = "../shared/iters.rs"]
+mod iters;
+use iters::Iters;
+
 fn main() {
     let ints = parse_input("./1/input");
     let increases = count_increases(ints);
@@ -8,11 +12,11 @@ fn main() {
 
 fn count_increases(readings: Vec<i32> ) -> usize
 {
-    return readings.windows(3)
-        .map(|triad| triad.iter().sum())
-        .collect::<Vec<i32>>()
-        .windows(2)
-        .filter(|pair| pair[1] > pair[0])
+    return readings.into_iter()
+        .windowed(3)
+        .map(|triad| triad.iter().sum::<i32>())
+        .pairwise()
+        .filter(|(prev, next)| next > prev)
         .count()
 }
 
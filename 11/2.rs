@@ -1,7 +1,13 @@
 use std::fs;
 
-type ParseTarget = Vec<Vec<usize>>;
-type Solution = usize;
+#[path = "../shared/automaton.rs"]
+mod automaton;
+use automaton::Automaton;
+mod octopus;
+use octopus::{Flashing, parse};
+
+type ParseTarget = Automaton<Flashing>;
+type Solution = u64;
 
 const EXAMPLES: [(&str, Solution); 1] = [
     ("1", 195)
@@ -44,9 +50,9 @@ fn main() {
     );
 }
 
-fn error<T>(msg: &str) -> Result<T, String> {
-    return Err(String::from(msg));
-}
+//fn error<T>(msg: &str) -> Result<T, String> {
+//    return Err(String::from(msg));
+//}
 
 fn operation(filename: String) -> Result<Solution, String> {
     return fs::read_to_string(filename)
@@ -55,89 +61,8 @@ fn operation(filename: String) -> Result<Solution, String> {
         .and_then(solve);
 }
 
-fn parse(contents: String) -> Result<ParseTarget, String> {
-    return contents.lines()
-        .map(|l| l.chars()
-            .map(|c| c.to_digit(10)
-                .map(|d| d as usize)
-                .ok_or(format!("Illegal character: {}", c))
-            ).collect()
-        ).collect();
-}
-
 fn solve(parsed: ParseTarget) -> Result<Solution, String> {
-    let mut octopuses = parsed.clone();
-    let count: usize = octopuses.iter().map(|row| row.len()).sum();
-    for step in 1.. {
-        charge(&mut octopuses);
-        if count == flash(&mut octopuses) {
-            return Ok(step);
-        }
-        reset(&mut octopuses);
-    }
-
-    return error("Somehow exited infinite loop");
-}
-
-fn charge(octopodes: &mut Vec<Vec<usize>>) {
-    for x in 0..octopodes.len() {
-        for y in 0..octopodes[x].len() {
-            octopodes[x][y] = octopodes[x][y] + 1;
-        }
-    }
-}
-
-fn reset(cephalopods: &mut Vec<Vec<usize>>) {
-    for x in 0..cephalopods.len() {
-        for y in 0..cephalopods[x].len() {
-            if cephalopods[x][y] == 10 {
-                cephalopods[x][y] = 0;
-            }
-        }
-    }
-}
-
-fn flash(octopi: &mut Vec<Vec<usize>>) -> usize {
-    let mut flashes: Vec<(usize, usize)> = octopi.iter().enumerate()
-        .flat_map(|(x, r)| r.iter().enumerate()
-            .flat_map(move |(y, v)| if v == &10 {
-                    Some((x, y))
-                } else {
-                    None
-                })
-        ).collect();
-    let mut flasher = 0;
-    while flasher < flashes.len() {
-        let (x, y) = flashes[flasher];
-        for (nx, ny) in neighbors(x, y).iter() {
-            let nval = octopi.get(*nx)
-                .and_then(|row| row.get(*ny));
-            match nval {
-                Some(10) | None => (), //Already flashed, or out of bounds.
-                Some(v) => {
-                    if v == &9 { //meaning v + 1 = 10
-                        flashes.push((*nx, *ny));
-                    }
-                    octopi[*nx][*ny] = v + 1;
-                }
-            }
-        }
-        flasher += 1;
-    }
-    return flasher;
-}
-
-fn neighbors(x: usize, y:usize) -> Vec<(usize, usize)> {
-    return (-1..=1).flat_map(
-        |dx| (-1..=1)
-            .map(move |dy| (dx, dy))
-    ).filter(|t| t != &(0, 0))
-    .flat_map(|(dx, dy)| tup (
-        if dx == -1 { x.checked_sub(1) } else { Some(x + (dx as usize)) },
-        if dy == -1 { y.checked_sub(1) } else { Some(y + (dy as usize)) }
-    )).collect();
-}
-
-fn tup<T>(x: Option<T>, y: Option<T>) -> Option<(T, T)> {
-    return Some((x?, y?));
+    let mut octopuses = parsed;
+    let count = octopuses.cells.len();
+    return octopuses.run_until(|stats| stats.fired == count).map(|stats| stats.step);
 }
@@ -0,0 +1,147 @@
+use std::env;
+use std::fs;
+
+#[path = "../shared/automaton.rs"]
+mod automaton;
+use automaton::{Automaton, Cycle};
+mod octopus;
+use octopus::{Flashing, parse};
+
+const DAY: u8 = 11;
+
+// Grids, the step they first get back to, and how long they take to get back there.
+const CYCLES: [(&str, u64, u64); 2] = [
+    ("1", 195, 10),
+    ("2", 0, 9)
+];
+
+// Grids and the first step every octopus flashes at once in, or why that never happens.
+const SYNCHRONISED: [(&str, Result<u64, &str>); 2] = [
+    ("1", Ok(195)),
+    ("2", Err("Goes round every 9 steps from step 0 without ever being done"))
+];
+
+// Grids, a number of steps, and how many flashes there have been by then.
+const FLASHES: [(&str, u64, u64); 7] = [
+    ("1", 10, 204),
+    ("1", 100, 1656),
+    ("1", 195, 3125),
+    ("1", 1234, 13425),
+    ("1", 1_000_000_000_000, 10_000_000_001_125),
+    ("2", 100, 22),
+    ("2", 1_000_000_000_000, 222_222_222_222)
+];
+
+// Usage: cycle [--steps N] [grid files...]
+// Says where each grid starts going round, when every octopus in it first flashes at once (if
+// that ever happens), and how many flashes there have been by step N - a trillion unless told
+// otherwise.  Defaults to the puzzle input.
+fn main() {
+    let results = CYCLES.iter()
+        .map(|(name, start, length)| (
+            format!("{} cycle", name),
+            read(format!("{}/{}.ie", DAY, name))
+                .and_then(|mut octopuses| octopuses.find_cycle())
+                .and_then(|actual| if (Cycle { start: *start, length: *length }) == actual {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {} steps from step {} but got {:?}", length, start, actual));
+                })
+        ))
+        .chain(SYNCHRONISED.iter().map(|(name, expected)| (
+            format!("{} synchronised", name),
+            read(format!("{}/{}.ie", DAY, name))
+                .map(|mut octopuses| synchronised(&mut octopuses))
+                .and_then(|actual| if *expected == actual.as_ref().map(|step| *step).map_err(String::as_str) {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {:?} but got {:?}", expected, actual));
+                })
+        )))
+        .chain(FLASHES.iter().map(|(name, steps, expected)| (
+            format!("{} after {} steps", name, steps),
+            check_flashes(format!("{}/{}.ie", DAY, name), *steps)
+                .and_then(|actual| if *expected == actual {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {} but got {}", expected, actual));
+                })
+        )))
+        .collect::<Vec<(String, Result<(), String>)>>();
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("Example {} passed.", name),
+            Err(msg) => println!("Example {} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("Please address errors before watching any octopuses.")
+    }
+
+    let (steps, mut files) = parse_args(env::args().skip(1).collect()).expect("Bad arguments.");
+    if files.len() == 0 {
+        files.push(format!("{}/input", DAY));
+    }
+    for filename in files.iter() {
+        match report(filename, steps) {
+            Ok(report) => println!("{}: {}", filename, report),
+            Err(msg) => println!("{} failed: {}.", filename, msg)
+        }
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<(u64, Vec<String>), String> {
+    let mut steps = 1_000_000_000_000;
+    let mut files = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--steps" {
+            let value = args.next().ok_or(format!("{} needs a value", arg))?;
+            steps = value.parse().map_err(|e| format!("Bad value for {}: {}", arg, e))?;
+        } else {
+            files.push(arg);
+        }
+    }
+    return Ok((steps, files));
+}
+
+fn read(filename: String) -> Result<Automaton<Flashing>, String> {
+    return fs::read_to_string(filename)
+        .map_err(|io_error| format!("{}", io_error))
+        .and_then(parse);
+}
+
+fn synchronised(octopuses: &mut Automaton<Flashing>) -> Result<u64, String> {
+    let count = octopuses.cells.len();
+    return octopuses.run_until(|stats| stats.fired == count).map(|stats| stats.step);
+}
+
+// Going round the cycle should come to the same as going step by step, as far as there's time
+// to check.
+fn check_flashes(filename: String, steps: u64) -> Result<u64, String> {
+    let flashes = read(filename.clone())?.fast_forward(steps)?;
+    if steps <= 10_000 {
+        let counted = read(filename)?.run(steps).iter().map(|stats| stats.fired as u64).sum::<u64>();
+        if counted != flashes {
+            return Err(format!("Fast forwarding found {} flashes but stepping found {}", flashes, counted));
+        }
+    }
+    return Ok(flashes);
+}
+
+fn report(filename: &String, steps: u64) -> Result<String, String> {
+    let cycle = read(filename.clone())?.find_cycle()?;
+    let synchronised = match synchronised(&mut read(filename.clone())?) {
+        Ok(step) => format!("all flash in step {}", step),
+        Err(msg) => format!("never all flash: {}", msg)
+    };
+    let flashes = read(filename.clone())?.fast_forward(steps)?;
+    return Ok(format!(
+        "goes round every {} steps from step {}, {}, {} flashes by step {}",
+        cycle.length,
+        cycle.start,
+        synchronised,
+        flashes,
+        steps
+    ));
+}
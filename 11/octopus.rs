@@ -0,0 +1,49 @@
+// Shared by every day 11 program, none of which uses all of it.
+#![allow(dead_code)]
+
+use automaton::{Automaton, Edges, Rule};
+
+const SURROUNDING: [(i64, i64); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+// Every octopus gains a unit of energy each step, and past 9 it flashes, giving one more to every
+// octopus around it.  Those that flashed are left with none.
+pub struct Flashing;
+
+impl Rule for Flashing {
+    type Cell = u8;
+
+    fn neighbourhood(&self) -> &[(i64, i64)] {
+        return &SURROUNDING;
+    }
+
+    fn edges(&self) -> Edges {
+        return Edges::Bounded;
+    }
+
+    fn update(&self, _phase: usize, energy: u8, _neighbours: &[u8]) -> u8 {
+        return energy + 1;
+    }
+
+    fn fires(&self, energy: u8) -> bool {
+        return energy > 9;
+    }
+
+    fn cascade(&self, energy: u8) -> u8 {
+        return energy.saturating_add(1);
+    }
+
+    fn settle(&self, _energy: u8) -> u8 {
+        return 0;
+    }
+}
+
+pub fn parse(contents: String) -> Result<Automaton<Flashing>, String> {
+    let rows = contents.lines()
+        .map(|l| l.chars()
+            .map(|c| c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or(format!("Illegal character: {}", c))
+            ).collect()
+        ).collect::<Result<Vec<Vec<u8>>, String>>()?;
+    return Automaton::new(Flashing, rows, 0);
+}
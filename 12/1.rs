@@ -1,16 +1,16 @@
 use std::fs;
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::hash::Hash;
 
-type CaveMap = HashMap<String, HashSet<String>>;
+mod caves;
+use caves::{CaveMap, Caves, Policy, count_paths};
+
 type ParseTarget = CaveMap;
-type Solution = usize;
+type Solution = u64;
 
-const EXAMPLES: [(&str, Solution); 3] = [
+const EXAMPLES: [(&str, Solution); 4] = [
     ("1", 10),
     ("2", 19),
-    ("3", 226)
+    ("3", 226),
+    ("4", 3515625)
 ];
 
 const DAY: u8 = 12;
@@ -50,9 +50,9 @@ fn main() {
     );
 }
 
-fn error<T>(msg: &str) -> Result<T, String> {
-    return Err(String::from(msg));
-}
+//fn error<T>(msg: &str) -> Result<T, String> {
+//    return Err(String::from(msg));
+//}
 
 fn operation(filename: String) -> Result<Solution, String> {
     return fs::read_to_string(filename)
@@ -62,61 +62,9 @@ fn operation(filename: String) -> Result<Solution, String> {
 }
 
 fn parse(contents: String) -> Result<ParseTarget, String> {
-    let mut to_return = HashMap::new();
-    for line in contents.lines() {
-        if let [lhs, rhs] = &line.split("-").map(String::from).collect::<Vec<String>>()[..] {
-            connect(&mut to_return, lhs, rhs);
-            connect(&mut to_return, rhs, lhs);
-        } else {
-            return error("Unparseable line.");
-        }
-    }
-    return Ok(to_return);
-}
-
-fn connect(map: &mut CaveMap, start: &String, end: &String) {
-    if ! map.contains_key(start) {
-        let new = HashSet::new();
-        map.insert(start.clone(), new);
-    }
-    map.get_mut(start)
-        .unwrap() // Guaranteed to be there line 79
-        .insert(end.clone());
+    return caves::parse(contents);
 }
 
 fn solve(parsed: ParseTarget) -> Result<Solution, String> {
-    return Ok(count_paths_to_end(
-        &String::from("start"),
-        &(vec!(String::from("start")).into_iter().collect::<HashSet<String>>()),
-        &parsed
-    ));
-}
-
-fn count_paths_to_end(curr: &String, seen: &HashSet<String>, map: &CaveMap) -> usize {
-    if curr == &"end" {
-        return 1;
-    }
-    return map.get(curr)
-        .unwrap() //Only dangerous for 'start'
-        .iter()
-        .filter(|s| is_big_cave(s) || !seen.contains(*s))
-        .map(|s| copy_and_add(seen, s, |new_seen| count_paths_to_end(s, new_seen, map)))
-        .sum::<usize>();
-}
-
-fn is_big_cave(name: &String) -> bool {
-    return name.chars()
-        .all(char::is_uppercase);
-}
-
-fn copy_and_add<T, F, R>(set: &HashSet<T>, elem: &T, f: F) -> R
-    where T: Clone + Hash + Eq,
-        F: FnOnce(&HashSet<T>) -> R
-{
-    if set.contains(elem) {
-        return f(set);
-    }
-    let mut augmented = set.clone();
-    augmented.insert(elem.clone());
-    return f(&augmented);
+    return count_paths(&Caves::intern(&parsed)?, &Policy::puzzle(0));
 }
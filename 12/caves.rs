@@ -0,0 +1,395 @@
+// Shared by every day 12 program, none of which uses all of it.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+pub type CaveMap = HashMap<String, HashSet<String>>;
+
+pub fn parse(contents: String) -> Result<CaveMap, String> {
+    let mut to_return = HashMap::new();
+    for line in contents.lines() {
+        if let [lhs, rhs] = &line.split("-").map(String::from).collect::<Vec<String>>()[..] {
+            connect(&mut to_return, lhs, rhs);
+            connect(&mut to_return, rhs, lhs);
+        } else {
+            return Err(String::from("Unparseable line."));
+        }
+    }
+    return Ok(to_return);
+}
+
+fn connect(map: &mut CaveMap, start: &String, end: &String) {
+    if ! map.contains_key(start) {
+        let new = HashSet::new();
+        map.insert(start.clone(), new);
+    }
+    map.get_mut(start)
+        .unwrap() // Guaranteed to be there, we just put it there
+        .insert(end.clone());
+}
+
+pub fn is_big_cave(name: &str) -> bool {
+    return name.chars()
+        .all(char::is_uppercase);
+}
+
+// The same map with every cave numbered, in name order.
+pub struct Caves {
+    pub names: Vec<String>,
+    pub links: Vec<Vec<usize>>
+}
+
+impl Caves {
+    pub fn intern(map: &CaveMap) -> Result<Caves, String> {
+        let mut names = map.keys().cloned().collect::<Vec<String>>();
+        names.sort();
+        let ids: HashMap<&String, usize> = names.iter()
+            .enumerate()
+            .map(|(i, name)| (name, i))
+            .collect();
+        let links = names.iter()
+            .map(|name| {
+                let mut linked = map[name].iter().map(|n| ids[n]).collect::<Vec<usize>>();
+                linked.sort();
+                return linked;
+            })
+            .collect();
+        return Ok(Caves { names: names, links: links });
+    }
+
+    pub fn id(&self, name: &str) -> Result<usize, String> {
+        return self.names.iter()
+            .position(|n| n == name)
+            .ok_or(format!("No cave called {}", name));
+    }
+}
+
+// Where paths run, and where they may go on the way.  Every cave may be visited as often as its
+// limit allows - big caves without limit and small ones once, unless told otherwise - and then
+// `extra_visits` more times between all of them, except for going back to the start.  The start
+// counts as small, whatever it's called.
+pub struct Policy {
+    pub start: String,
+    pub end: String,
+    pub limits: HashMap<String, u8>,
+    pub extra_visits: u8,
+    pub forbidden: Vec<(String, String)>
+}
+
+impl Policy {
+    // Part 1 is no extra visits, part 2 is one.
+    pub fn puzzle(extra_visits: u8) -> Policy {
+        return Policy {
+            start: String::from("start"),
+            end: String::from("end"),
+            limits: HashMap::new(),
+            extra_visits: extra_visits,
+            forbidden: Vec::new()
+        };
+    }
+
+    fn limit(&self, name: &str) -> Option<u8> {
+        return match self.limits.get(name) {
+            Some(limit) => Some(*limit),
+            None if name == self.start => Some(1),
+            None if is_big_cave(name) => None,
+            None => Some(1)
+        };
+    }
+}
+
+// A policy worked out against a particular set of caves.  Every cave with a limit gets a few bits
+// of a u128 to count its visits in, just enough to reach the most it could ever be visited.
+// `fields` holds where each cave's bits start, and how many there are.
+struct Rules {
+    start: usize,
+    end: usize,
+    limits: Vec<Option<u8>>,
+    fields: Vec<(u32, u32)>,
+    extra_visits: u8,
+    forbidden: HashSet<(usize, usize)>
+}
+
+impl Rules {
+    fn new(caves: &Caves, policy: &Policy) -> Result<Rules, String> {
+        let start = caves.id(&policy.start)?;
+        let end = caves.id(&policy.end)?;
+        for name in policy.limits.keys() {
+            caves.id(name)?;
+        }
+        let mut forbidden = HashSet::new();
+        for (lhs, rhs) in policy.forbidden.iter() {
+            let (lhs, rhs) = (caves.id(lhs)?, caves.id(rhs)?);
+            forbidden.insert((lhs, rhs));
+            forbidden.insert((rhs, lhs));
+        }
+
+        let limits = caves.names.iter()
+            .map(|name| policy.limit(name))
+            .collect::<Vec<Option<u8>>>();
+        // Two unlimited caves next to each other make for paths that go back and forth between them
+        // forever, if they can be got to at all.
+        let reached = spread(caves, start, end, &forbidden);
+        for (lhs, linked) in caves.links.iter().enumerate() {
+            for rhs in linked.iter().filter(|rhs| lhs <= **rhs) {
+                if reached[lhs] && lhs != end && *rhs != end && limits[lhs].is_none() && limits[*rhs].is_none()
+                    && !forbidden.contains(&(lhs, *rhs)) {
+                    return Err(format!(
+                        "Paths can go back and forth along {}-{} forever",
+                        caves.names[lhs],
+                        caves.names[*rhs]
+                    ));
+                }
+            }
+        }
+        let mut fields = Vec::new();
+        let mut used = 0;
+        for (cave, limit) in limits.iter().enumerate() {
+            let most = match limit {
+                None => 0,
+                // However limited, every path visits the start.
+                Some(limit) if cave == start => (*limit).max(1),
+                Some(limit) => limit.saturating_add(policy.extra_visits)
+            };
+            let width = 8 - most.leading_zeros();
+            fields.push((used, width));
+            used += width;
+        }
+        if used > 128 {
+            return Err(String::from("Too many limited caves to keep track of"));
+        }
+        return Ok(Rules {
+            start: start,
+            end: end,
+            limits: limits,
+            fields: fields,
+            extra_visits: policy.extra_visits,
+            forbidden: forbidden
+        });
+    }
+}
+
+// Which caves can be got to from `from` without going through `stop` or along a forbidden link,
+// however often they may be visited.
+fn spread(caves: &Caves, from: usize, stop: usize, forbidden: &HashSet<(usize, usize)>) -> Vec<bool> {
+    let mut reached = vec![false; caves.names.len()];
+    reached[from] = true;
+    let mut queue = vec![from];
+    while let Some(curr) = queue.pop() {
+        if curr == stop && curr != from {
+            continue;
+        }
+        for next in caves.links[curr].iter() {
+            if !reached[*next] && !forbidden.contains(&(curr, *next)) {
+                reached[*next] = true;
+                queue.push(*next);
+            }
+        }
+    }
+    return reached;
+}
+
+// Caves no path can go through: those that can't be got to from the start, and those the end
+// can't be got to from.
+pub fn unreachable(caves: &Caves, policy: &Policy) -> Result<Vec<String>, String> {
+    let rules = Rules::new(caves, policy)?;
+    let from_start = spread(caves, rules.start, rules.end, &rules.forbidden);
+    let to_end = spread(caves, rules.end, rules.start, &rules.forbidden);
+    return Ok(
+        caves.names.iter()
+            .enumerate()
+            .filter(|(cave, _)| !from_start[*cave] || !to_end[*cave])
+            .map(|(_, name)| name.clone())
+            .collect()
+    );
+}
+
+// How many times each limited cave has been visited so far, and how many extra visits are used.
+#[derive(Clone, Copy)]
+pub struct Tracker {
+    visits: u128,
+    extra: u8
+}
+
+impl Tracker {
+    fn new(rules: &Rules) -> Tracker {
+        // The path starts by visiting the start.
+        return Tracker { visits: 0, extra: 0 }.visit(rules, rules.start);
+    }
+
+    fn visits(&self, rules: &Rules, cave: usize) -> u8 {
+        let (shift, width) = rules.fields[cave];
+        return ((self.visits >> shift) & ((1 << width) - 1)) as u8;
+    }
+
+    fn can_visit(&self, rules: &Rules, cave: usize) -> bool {
+        let limit = match rules.limits[cave] {
+            // We can visit unlimited caves as many times as we want
+            None => return true,
+            Some(limit) => limit
+        };
+        if self.visits(rules, cave) < limit {
+            // We can go to any cave we've got visits left for
+            return true;
+        }
+        // Otherwise it's an extra visit, and we can never go back to start.
+        return cave != rules.start && self.extra < rules.extra_visits;
+    }
+
+    fn visit(&self, rules: &Rules, cave: usize) -> Tracker {
+        return match rules.limits[cave] {
+            // We don't track visits to unlimited caves.
+            None => *self,
+            Some(limit) => Tracker {
+                visits: self.visits + (1 << rules.fields[cave].0),
+                extra: if self.visits(rules, cave) < limit { self.extra } else { self.extra + 1 }
+            }
+        };
+    }
+}
+
+// Every path from start to end, remembering how many there are from each cave with each set of
+// visits behind it, since that's all that decides where a path can go next.
+struct Paths<'a> {
+    caves: &'a Caves,
+    rules: Rules,
+    memo: HashMap<(usize, u128, u8), u64>
+}
+
+impl Paths<'_> {
+    fn new<'a>(caves: &'a Caves, policy: &Policy) -> Result<Paths<'a>, String> {
+        return Ok(Paths { caves: caves, rules: Rules::new(caves, policy)?, memo: HashMap::new() });
+    }
+
+    fn start(&self) -> (usize, Tracker) {
+        return (self.rules.start, Tracker::new(&self.rules));
+    }
+
+    // Where a path can go from here, and what it will have seen when it gets there.
+    fn next(&self, curr: usize, seen: Tracker) -> Vec<(usize, Tracker)> {
+        if curr == self.rules.end {
+            return Vec::new();
+        }
+        return self.caves.links[curr].iter()
+            .filter(|next| !self.rules.forbidden.contains(&(curr, **next)) && seen.can_visit(&self.rules, **next))
+            .map(|next| (*next, seen.visit(&self.rules, *next)))
+            .collect();
+    }
+
+    fn count_to_end(&mut self, curr: usize, seen: Tracker) -> Result<u64, String> {
+        if curr == self.rules.end {
+            return Ok(1);
+        }
+        let key = (curr, seen.visits, seen.extra);
+        if let Some(count) = self.memo.get(&key) {
+            return Ok(*count);
+        }
+        let mut count: u64 = 0;
+        for (next, next_seen) in self.next(curr, seen) {
+            let paths = self.count_to_end(next, next_seen)?;
+            count = count.checked_add(paths).ok_or("Too many paths to count")?;
+        }
+        self.memo.insert(key, count);
+        return Ok(count);
+    }
+
+    // Depth first, in name order, skipping anywhere there's no way to the end from.  Stops as soon
+    // as `f` says so, returning whether it did.
+    fn each<F: FnMut(&[usize]) -> bool>(&mut self, path: &mut Vec<usize>, seen: Tracker, f: &mut F) -> Result<bool, String> {
+        let curr = path[path.len() - 1];
+        if curr == self.rules.end {
+            return Ok(!f(path));
+        }
+        for (next, next_seen) in self.next(curr, seen) {
+            if self.count_to_end(next, next_seen)? == 0 {
+                continue;
+            }
+            path.push(next);
+            let stopped = self.each(path, next_seen, f)?;
+            path.pop();
+            if stopped {
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+}
+
+pub fn count_paths(caves: &Caves, policy: &Policy) -> Result<u64, String> {
+    let mut paths = Paths::new(caves, policy)?;
+    let (start, seen) = paths.start();
+    return paths.count_to_end(start, seen);
+}
+
+// Hands every path to `f` in turn, until it returns false.
+pub fn each_path<F: FnMut(&[usize]) -> bool>(caves: &Caves, policy: &Policy, mut f: F) -> Result<(), String> {
+    let mut paths = Paths::new(caves, policy)?;
+    let (start, seen) = paths.start();
+    paths.each(&mut vec![start], seen, &mut f)?;
+    return Ok(());
+}
+
+// One path chosen uniformly at random, if there are any, given a way to pick a number below
+// another.  Each step goes somewhere with the chance of a path going through there.
+pub fn sample_path<R: FnMut(u64) -> u64>(caves: &Caves, policy: &Policy, mut below: R) -> Result<Option<Vec<usize>>, String> {
+    let mut paths = Paths::new(caves, policy)?;
+    let (mut curr, mut seen) = paths.start();
+    let mut path = vec![curr];
+    let count = paths.count_to_end(curr, seen)?;
+    if count == 0 {
+        return Ok(None);
+    }
+    let mut pick = below(count);
+    while curr != paths.rules.end {
+        let mut chosen = None;
+        for (next, next_seen) in paths.next(curr, seen) {
+            let through = paths.count_to_end(next, next_seen)?;
+            if pick < through {
+                chosen = Some((next, next_seen));
+                break;
+            }
+            pick -= through;
+        }
+        match chosen {
+            Some((next, next_seen)) => {
+                curr = next;
+                seen = next_seen;
+                path.push(curr);
+            },
+            None => return Ok(None)
+        }
+    }
+    return Ok(Some(path));
+}
+
+// `start,A,b,A,end`
+pub fn describe_path(caves: &Caves, path: &[usize]) -> String {
+    return path.iter()
+        .map(|cave| caves.names[*cave].as_str())
+        .collect::<Vec<&str>>()
+        .join(",");
+}
+
+// The map as an undirected Graphviz graph: big caves are filled boxes, small ones plain ellipses.
+pub fn to_dot(map: &CaveMap) -> String {
+    let mut names = map.keys().collect::<Vec<&String>>();
+    names.sort();
+    let mut lines = vec![String::from("graph caves {")];
+    for name in names.iter() {
+        if is_big_cave(name) {
+            lines.push(format!("    \"{}\" [shape=box, style=filled, fillcolor=lightgrey];", name));
+        } else {
+            lines.push(format!("    \"{}\" [shape=ellipse];", name));
+        }
+    }
+    for name in names.iter() {
+        let mut linked = map[*name].iter().filter(|other| other > name).collect::<Vec<&String>>();
+        linked.sort();
+        for other in linked {
+            lines.push(format!("    \"{}\" -- \"{}\";", name, other));
+        }
+    }
+    lines.push(String::from("}"));
+    return lines.join("\n");
+}
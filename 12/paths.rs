@@ -0,0 +1,399 @@
+use std::env;
+use std::fs;
+use std::collections::HashSet;
+
+#[path = "../shared/random.rs"]
+mod random;
+use random::Random;
+mod caves;
+use caves::{Caves, Policy, count_paths, describe_path, each_path, parse, sample_path, to_dot, unreachable};
+
+const DAY: u8 = 12;
+
+// A cave map, the policy to walk it by as arguments, and how many paths there are.
+const EXAMPLES: [(&str, &[&str], u64); 22] = [
+    ("1", &[], 10),
+    ("1", &["--extra", "1"], 36),
+    ("1", &["--extra", "2"], 101),
+    ("1", &["--limit", "b=0"], 2),
+    ("1", &["--limit", "b=2"], 30),
+    ("1", &["--limit", "A=2"], 8),
+    ("1", &["--limit", "A=2", "--limit", "b=3", "--extra", "1"], 59),
+    ("1", &["--forbid", "A-b", "--extra", "1"], 5),
+    ("1", &["--start", "c", "--end", "d"], 9),
+    ("1", &["--start", "end", "--end", "start", "--extra", "1"], 36),
+    ("2", &["--extra", "2"], 449),
+    ("2", &["--limit", "HN=3", "--extra", "1"], 97),
+    ("2", &["--limit", "dc=0"], 3),
+    ("2", &["--forbid", "kj-HN", "--forbid", "dc-end", "--extra", "1"], 13),
+    ("2", &["--start", "kj", "--end", "LN", "--extra", "1"], 108),
+    ("5", &["--forbid", "A-B"], 1),
+    ("5", &["--limit", "A=1"], 2),
+    ("5", &["--start", "c"], 0),
+    ("1", &["--start", "A"], 3),
+    ("1", &["--start", "A", "--extra", "1"], 6),
+    ("2", &["--start", "HN", "--extra", "1"], 22),
+    ("2", &["--start", "LN", "--end", "start", "--extra", "1"], 118)
+];
+
+// Policies that make no sense for the map they're given, and why.
+const REJECTED: [(&str, &[&str], &str); 5] = [
+    ("1", &["--limit", "zz=1"], "No cave called zz"),
+    ("1", &["--forbid", "A-zz"], "No cave called zz"),
+    ("1", &["--start", "zz"], "No cave called zz"),
+    ("5", &["--end", "zz"], "No cave called zz"),
+    ("5", &[], "Paths can go back and forth along A-B forever")
+];
+
+// Caves no path can go through.
+const UNREACHABLE: [(&str, &[&str], &[&str]); 4] = [
+    ("1", &[], &[]),
+    ("1", &["--forbid", "b-d"], &["d"]),
+    ("5", &["--forbid", "A-B"], &["B", "c", "d"]),
+    ("5", &["--start", "c"], &["A", "B", "b", "c", "d", "end", "start"])
+];
+
+// Every path through a map, in order.
+const LISTED: [(&str, &[&str], &[&str]); 4] = [
+    (
+        "1",
+        &[],
+        &[
+            "start,A,b,A,c,A,end",
+            "start,A,b,A,end",
+            "start,A,b,end",
+            "start,A,c,A,b,A,end",
+            "start,A,c,A,b,end",
+            "start,A,c,A,end",
+            "start,A,end",
+            "start,b,A,c,A,end",
+            "start,b,A,end",
+            "start,b,end"
+        ]
+    ),
+    ("1", &["--max", "3"], &["start,A,b,A,c,A,end", "start,A,b,A,end", "start,A,b,end"]),
+    ("1", &["--max", "0"], &[]),
+    ("1", &["--max", "10"], &[
+        "start,A,b,A,c,A,end",
+        "start,A,b,A,end",
+        "start,A,b,end",
+        "start,A,c,A,b,A,end",
+        "start,A,c,A,b,end",
+        "start,A,c,A,end",
+        "start,A,end",
+        "start,b,A,c,A,end",
+        "start,b,A,end",
+        "start,b,end"
+    ])
+];
+
+// Maps to sample paths from, how many samples to take per path, and how far from that any path's
+// share may stray.
+const SAMPLED: [(&str, &[&str], u64, f64); 2] = [
+    ("1", &[], 1000, 0.15),
+    ("2", &["--extra", "1"], 200, 0.3)
+];
+
+// Maps and policies with no paths to sample.
+const UNSAMPLED: [(&str, &[&str]); 2] = [
+    ("5", &["--start", "c"]),
+    ("1", &["--start", "d", "--end", "c", "--forbid", "b-d"])
+];
+
+const DOT: [(&str, &str); 1] = [
+    (
+        "1",
+        "graph caves {
+    \"A\" [shape=box, style=filled, fillcolor=lightgrey];
+    \"b\" [shape=ellipse];
+    \"c\" [shape=ellipse];
+    \"d\" [shape=ellipse];
+    \"end\" [shape=ellipse];
+    \"start\" [shape=ellipse];
+    \"A\" -- \"b\";
+    \"A\" -- \"c\";
+    \"A\" -- \"end\";
+    \"A\" -- \"start\";
+    \"b\" -- \"d\";
+    \"b\" -- \"end\";
+    \"b\" -- \"start\";
+}"
+    )
+];
+
+enum Mode {
+    Count,
+    List,
+    Sample(u64),
+    Dot,
+    Unreachable
+}
+
+struct Options {
+    policy: Policy,
+    mode: Mode,
+    max: Option<u64>,
+    seed: u64,
+    files: Vec<String>
+}
+
+// Usage: paths [--start NAME] [--end NAME] [--limit CAVE=N]... [--extra N] [--forbid A-B]...
+//              [--list [--max N] | --sample N [--seed S] | --dot | --unreachable] [map files...]
+// Small caves may be visited once and big ones any number of times, unless limited otherwise.
+// `--extra` allows that many more visits between all the caves, other than back to the start.
+// Counts paths unless asked to list them, sample some, draw the map, or name the caves no path
+// goes through.  Defaults to part 1 of the puzzle on the puzzle input.
+fn main() {
+    let results = EXAMPLES.iter()
+        .map(|(name, args, expected)| (
+            format!("{} [{}]", name, args.join(" ")),
+            operation(format!("{}/{}.ie", DAY, name), args)
+                .and_then(|actual| if *expected == actual {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {} but got {}", expected, actual));
+                })
+                .and_then(|_| check_listing(format!("{}/{}.ie", DAY, name), args, *expected))
+        ))
+        .chain(REJECTED.iter().map(|(name, args, expected)| (
+            format!("{} [{}]", name, args.join(" ")),
+            match operation(format!("{}/{}.ie", DAY, name), args) {
+                Ok(actual) => Err(format!("Expected '{}' but counted {}", expected, actual)),
+                Err(msg) => if *expected == msg {
+                    Ok(())
+                } else {
+                    Err(format!("Expected '{}' but got '{}'", expected, msg))
+                }
+            }
+        )))
+        .chain(LISTED.iter().map(|(name, args, expected)| (
+            format!("{} listed [{}]", name, args.join(" ")),
+            read(format!("{}/{}.ie", DAY, name))
+                .and_then(|caves| parse_args(args).and_then(|options| list(&caves, &options)))
+                .and_then(|actual| if *expected == &actual[..] {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {:?} but got {:?}", expected, actual));
+                })
+        )))
+        .chain(SAMPLED.iter().map(|(name, args, per_path, tolerance)| (
+            format!("{} sampled [{}]", name, args.join(" ")),
+            check_sampling(format!("{}/{}.ie", DAY, name), args, *per_path, *tolerance)
+        )))
+        .chain(UNSAMPLED.iter().map(|(name, args)| (
+            format!("{} unsampled [{}]", name, args.join(" ")),
+            read(format!("{}/{}.ie", DAY, name))
+                .and_then(|caves| parse_args(args)
+                    .and_then(|options| sample_path(&caves, &options.policy, |n| n - 1))
+                    .map(|path| path.map(|path| describe_path(&caves, &path)))
+                )
+                .and_then(|sampled| match sampled {
+                    None => Ok(()),
+                    Some(path) => Err(format!("Expected no path but sampled {}", path))
+                })
+        )))
+        .chain(UNREACHABLE.iter().map(|(name, args, expected)| (
+            format!("{} unreachable [{}]", name, args.join(" ")),
+            read(format!("{}/{}.ie", DAY, name))
+                .and_then(|caves| parse_args(args).and_then(|options| unreachable(&caves, &options.policy)))
+                .and_then(|actual| if *expected == &actual[..] {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {:?} but got {:?}", expected, actual));
+                })
+        )))
+        .chain(DOT.iter().map(|(name, expected)| (
+            format!("{} [--dot]", name),
+            fs::read_to_string(format!("{}/{}.ie", DAY, name))
+                .map_err(|io_error| format!("{}", io_error))
+                .and_then(parse)
+                .map(|map| to_dot(&map))
+                .and_then(|actual| if *expected == actual {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected\n{}\nbut got\n{}\n", expected, actual));
+                })
+        )))
+        .collect::<Vec<(String, Result<(), String>)>>();
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("Example {} passed.", name),
+            Err(msg) => println!("Example {} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("Please address errors before counting any paths.")
+    }
+
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let mut options = parse_args(&args.iter().map(String::as_str).collect::<Vec<&str>>())
+        .expect("Bad arguments.");
+    if options.files.len() == 0 {
+        options.files.push(format!("{}/input", DAY));
+    }
+    for filename in options.files.iter() {
+        if let Err(msg) = run(filename, &options) {
+            println!("{} failed: {}.", filename, msg);
+        }
+    }
+}
+
+fn run(filename: &String, options: &Options) -> Result<(), String> {
+    if let Mode::Dot = options.mode {
+        let map = fs::read_to_string(filename)
+            .map_err(|io_error| format!("{}", io_error))
+            .and_then(parse)?;
+        println!("{}", to_dot(&map));
+        return Ok(());
+    }
+    let caves = read(filename.clone())?;
+    match options.mode {
+        Mode::List => {
+            // Paths go out as they're found, there may be far too many to hold on to.
+            let mut listed = 0;
+            return each_path(&caves, &options.policy, |path| {
+                if options.max.map(|max| listed >= max).unwrap_or(false) {
+                    return false;
+                }
+                println!("{}", describe_path(&caves, path));
+                listed += 1;
+                return true;
+            });
+        },
+        Mode::Unreachable => {
+            println!("{}: {}", filename, unreachable(&caves, &options.policy)?.join(","));
+            return Ok(());
+        },
+        Mode::Sample(n) => {
+            let mut random = Random::new(options.seed);
+            for _ in 0..n {
+                match sample_path(&caves, &options.policy, |n| random.below(n))? {
+                    Some(path) => println!("{}", describe_path(&caves, &path)),
+                    None => return Err(String::from("There are no paths to sample"))
+                }
+            }
+            return Ok(());
+        },
+        _ => {
+            println!("{}: {}", filename, count_paths(&caves, &options.policy)?);
+            return Ok(());
+        }
+    }
+}
+
+fn read(filename: String) -> Result<Caves, String> {
+    return fs::read_to_string(filename)
+        .map_err(|io_error| format!("{}", io_error))
+        .and_then(parse)
+        .and_then(|map| Caves::intern(&map));
+}
+
+fn operation(filename: String, args: &[&str]) -> Result<u64, String> {
+    let options = parse_args(args)?;
+    return count_paths(&read(filename)?, &options.policy);
+}
+
+fn list(caves: &Caves, options: &Options) -> Result<Vec<String>, String> {
+    let mut listed = Vec::new();
+    each_path(caves, &options.policy, |path| {
+        if options.max.map(|max| listed.len() as u64 >= max).unwrap_or(false) {
+            return false;
+        }
+        listed.push(describe_path(caves, path));
+        return true;
+    })?;
+    return Ok(listed);
+}
+
+// Listing every path should find as many as were counted, none of them twice.
+fn check_listing(filename: String, args: &[&str], count: u64) -> Result<(), String> {
+    let listed = list(&read(filename)?, &parse_args(args)?)?;
+    if listed.len() as u64 != count {
+        return Err(format!("Counted {} paths but listed {}", count, listed.len()));
+    }
+    if listed.iter().collect::<HashSet<&String>>().len() != listed.len() {
+        return Err(String::from("Listed the same path twice"));
+    }
+    return Ok(());
+}
+
+// Every path should turn up about as often as every other, and nothing else should turn up.
+fn check_sampling(filename: String, args: &[&str], per_path: u64, tolerance: f64) -> Result<(), String> {
+    let caves = read(filename)?;
+    let options = parse_args(args)?;
+    let listed = list(&caves, &options)?;
+    let mut random = Random::new(listed.len() as u64);
+    let mut seen = listed.iter().map(|path| (path.clone(), 0)).collect::<Vec<(String, u64)>>();
+    for _ in 0..per_path * listed.len() as u64 {
+        let path = sample_path(&caves, &options.policy, |n| random.below(n))?
+            .map(|path| describe_path(&caves, &path))
+            .ok_or("No path sampled")?;
+        match seen.iter_mut().find(|(p, _)| *p == path) {
+            Some((_, times)) => *times += 1,
+            None => return Err(format!("Sampled {}, which isn't a path", path))
+        }
+    }
+    for (path, times) in seen {
+        if (times as f64 - per_path as f64).abs() > per_path as f64 * tolerance {
+            return Err(format!("Sampled {} {} times, expected about {}", path, times, per_path));
+        }
+    }
+    return Ok(());
+}
+
+fn parse_args(args: &[&str]) -> Result<Options, String> {
+    let mut options = Options {
+        policy: Policy::puzzle(0),
+        mode: Mode::Count,
+        max: None,
+        seed: 1,
+        files: Vec::new()
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "--list" => {
+                options.mode = Mode::List;
+                continue;
+            },
+            "--dot" => {
+                options.mode = Mode::Dot;
+                continue;
+            },
+            "--unreachable" => {
+                options.mode = Mode::Unreachable;
+                continue;
+            },
+            "--start" | "--end" | "--limit" | "--extra" | "--forbid" | "--max" | "--sample" | "--seed" => (),
+            _ => {
+                options.files.push(arg.to_string());
+                continue;
+            }
+        }
+        let value = args.next().ok_or(format!("{} needs a value", arg))?;
+        let bad = |e: String| format!("Bad value for {}: {}", arg, e);
+        let number = || value.parse::<u64>().map_err(|e| bad(format!("{}", e)));
+        let policy = &mut options.policy;
+        match *arg {
+            "--start" => policy.start = value.to_string(),
+            "--end" => policy.end = value.to_string(),
+            "--extra" => policy.extra_visits = value.parse().map_err(|e| bad(format!("{}", e)))?,
+            "--limit" => {
+                let (cave, limit) = split_once(value, "=").map_err(bad)?;
+                policy.limits.insert(cave, limit.parse().map_err(|e| bad(format!("{}", e)))?);
+            },
+            "--forbid" => policy.forbidden.push(split_once(value, "-").map_err(bad)?),
+            "--max" => options.max = Some(number()?),
+            "--sample" => options.mode = Mode::Sample(number()?),
+            _ => options.seed = number()?
+        }
+    }
+    return Ok(options);
+}
+
+fn split_once(s: &str, separator: &str) -> Result<(String, String), String> {
+    if let [lhs, rhs] = &s.split(separator).collect::<Vec<&str>>()[..] {
+        return Ok((lhs.to_string(), rhs.to_string()));
+    }
+    return Err(format!("Expected two parts separated by '{}' but found '{}'", separator, s));
+}
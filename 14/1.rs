@@ -1,11 +1,18 @@
 use std::fs;
 use std::collections::HashMap;
 
-type ParseTarget = (Vec<char>, HashMap<(char, char), char>);
+#[path = "../shared/iters.rs"]
+mod iters;
+mod polymer;
+use iters::Iters;
+use polymer::{Rules, Template, parse};
+
+type ParseTarget = (Template, Rules);
 type Solution = usize;
 
-const EXAMPLES: [(&str, Solution); 1] = [
-    ("1", 1588)
+const EXAMPLES: [(&str, Solution); 2] = [
+    ("1", 1588),
+    ("2", 4794)
 ];
 
 const DAY: u8 = 14;
@@ -45,9 +52,9 @@ fn main() {
     );
 }
 
-fn error<T>(msg: &str) -> Result<T, String> {
-    return Err(String::from(msg));
-}
+//fn error<T>(msg: &str) -> Result<T, String> {
+//    return Err(String::from(msg));
+//}
 
 fn operation(filename: String) -> Result<Solution, String> {
     return fs::read_to_string(filename)
@@ -56,102 +63,29 @@ fn operation(filename: String) -> Result<Solution, String> {
         .and_then(solve);
 }
 
-fn parse(contents: String) -> Result<ParseTarget, String> {
-    let mut lines = contents.lines();
-    let template: Vec<char> = lines.next()
-        .ok_or("No lines?")?
-        .chars()
-        .collect();
-
-    if lines.next().is_none() {
-        return error("No empty line");
-    }
-    
-    let mut insertion_rules = HashMap::new();
-
-    for l in lines {
-        let first = l.chars().nth(0).ok_or("Malformed line".to_string())?;
-        let second = l.chars().nth(1).ok_or("Malformed line".to_string())?;
-        let insertion = l.chars().nth(6).ok_or("Malformed line".to_string())?;
-
-        insertion_rules.insert(
-            (first, second),
-            insertion
-        );
-    }
-        
-    return Ok((template, insertion_rules));
-}
-enum Twople<T> {
-    Pair(T, T),
-    Last(T)
-}
-
-struct TwopleWindows<T: Copy, I: Iterator<Item=T>> {
-    done: bool,
-    prev: Option<T>,
-    backer: I
-}
-
-impl<T: Copy, I: Iterator<Item=T>> TwopleWindows<T, I> {
-    fn new(backer: I) -> TwopleWindows<T, I> {
-        return TwopleWindows {
-            done: false,
-            prev: None,
-            backer: backer
-        };
-    }
-}
-
-impl<T: Copy, I: Iterator<Item=T>> Iterator for TwopleWindows<T, I> {
-    type Item = Twople<T>;
-    fn next(&mut self) -> Option<Twople<T>> {
-        if self.done {
-            return None;
-        }
-        let first = self.prev.or_else(|| self.backer.next())?;
-        match self.backer.next() {
-            Some(second) => {
-                self.prev = Some(second);
-                return Some(Twople::Pair(first, second));
-            }
-            None => {
-                self.done = true;
-                return Some(Twople::Last(first))
-            }
-        }
-    }
-}
-
 fn solve(parsed: ParseTarget) -> Result<Solution, String> {
     let (template, rules) = parsed;
     let result = inject(&mut template.into_iter(), &rules, 10);
-    let mut counts: HashMap<char, usize> = HashMap::new(); 
-    for c in result.iter() {
-        counts.insert(
-            *c,
-            *counts.get(c).unwrap_or(&0) + 1
-        );
-    }
+    let counts: HashMap<char, usize> = result.into_iter().counts();
 
     let max = counts.values().max().ok_or("No maximum".to_string())?;
     let min = counts.values().min().ok_or("No minimum".to_string())?;
     return Ok(*max - *min);
 }
 
-fn inject<T>(elements: T, rules: &HashMap<(char, char), char>, n: u8) -> Vec<char>
+fn inject<T>(elements: T, rules: &Rules, n: u8) -> Vec<char>
 where T: Iterator<Item = char>
 {
     if n == 0 {
         return elements.collect();
     }
-    let mut iter = TwopleWindows::new(elements)
+    let mut iter = elements.with_next()
         .flat_map(|t| match t {
-            Twople::Pair(l, r) => match rules.get(&(l, r)) {
-                Some(i) => vec![l, *i],
+            (l, Some(r)) => match rules.get(&(l, r)) {
+                Some(insertion) => [vec![l], insertion.clone()].concat(),
                 None => vec![l]
             },
-            Twople::Last(last) => vec![last]
+            (last, None) => vec![last]
         });
 
     return inject(&mut iter as &mut dyn Iterator<Item=char>, rules, n - 1);
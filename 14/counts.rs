@@ -0,0 +1,155 @@
+use std::env;
+use std::fs;
+
+mod polymer;
+use polymer::{Arithmetic, grow, parse, unruled_pairs};
+
+const DAY: u8 = 14;
+
+// A polymer file, how many steps to grow it, and every element's count afterwards.
+const EXAMPLES: [(&str, u64, Arithmetic, &str); 7] = [
+    ("1.ie", 0, Arithmetic::Checked, "B: 1, C: 1, N: 2"),
+    ("1.ie", 10, Arithmetic::Checked, "B: 1749, C: 298, H: 161, N: 865"),
+    (
+        "1.ie",
+        40,
+        Arithmetic::Checked,
+        "B: 2192039569602, C: 6597635301, H: 3849876073, N: 1096047802353"
+    ),
+    (
+        "1.ie",
+        100,
+        Arithmetic::Checked,
+        "B: 2535298019313139394077918899467, C: 2991263317955795288263161, H: 1757246543191084858126303, N: 1267649032861687663532044327198"
+    ),
+    (
+        "1.ie",
+        1000000000000,
+        Arithmetic::Modulo(1000000007),
+        "B: 999749691, C: 637758971, H: 389998280, N: 850591569"
+    ),
+    ("2.ie", 0, Arithmetic::Checked, "A: 2, B: 1, C: 1, D: 1 (no rule for AD)"),
+    ("2.ie", 8, Arithmetic::Checked, "A: 1174, B: 1129, C: 1129, D: 309 (no rule for AD)")
+];
+
+// Grown far enough that the counts no longer fit, and the error for it.
+const OVERFLOWING: [(&str, u64, &str); 1] = [
+    ("1.ie", 200, "Pair counts overflow a u128 - count modulo something instead")
+];
+
+// Polymer files that should be rejected, and why.
+const MALFORMED: [(&str, &str); 10] = [
+    ("", "No lines?"),
+    ("NNCB", "No empty line"),
+    ("\n\nNN -> C", "Line 1: expected a template but the line is empty"),
+    ("NNCB\nCH -> B", "Line 2: expected an empty line but found 'CH -> B'"),
+    ("NNCB\n\nCH -> B\n\n", "Line 4, column 1: expected an element but the line ended"),
+    ("NNCB\n\nC", "Line 3, column 2: expected an element but the line ended"),
+    ("NNCB\n\nCH", "Line 3, column 3: expected ' -> ' but the line ended"),
+    ("NNCB\n\nCH-> B", "Line 3, column 3: expected ' -> ' but found '-> B'"),
+    ("NNCB\n\nCH -> B\nNN -> C\nCH -> N", "Line 5: duplicate rule for CH, first given on line 3"),
+    ("NNCB\n\nCH -> B C", "Line 3, column 8: expected an element but found ' '")
+];
+
+// Usage: counts [--steps N] [--modulo M] [polymer files...]
+// Defaults to the puzzle's forty steps, checked, on the puzzle input.
+fn main() {
+    let results = EXAMPLES.iter()
+        .map(|(name, steps, arithmetic, expected)| (
+            format!("{} ({} steps)", name, steps),
+            operation(*steps, *arithmetic, format!("{}/{}", DAY, name))
+                .and_then(|actual| if *expected == actual {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {} but got {}", expected, actual));
+                })
+        ))
+        .chain(OVERFLOWING.iter().map(|(name, steps, expected)| (
+            format!("{} ({} steps)", name, steps),
+            match operation(*steps, Arithmetic::Checked, format!("{}/{}", DAY, name)) {
+                Ok(actual) => Err(format!("Expected '{}' but got {}", expected, actual)),
+                Err(msg) => if *expected == msg {
+                    Ok(())
+                } else {
+                    Err(format!("Expected '{}' but got '{}'", expected, msg))
+                }
+            }
+        )))
+        .chain(MALFORMED.iter().map(|(contents, expected)| (
+            format!("Malformed {:?}", contents),
+            match parse(contents.to_string()) {
+                Ok(_) => Err(format!("Expected '{}' but it parsed", expected)),
+                Err(msg) => if *expected == msg {
+                    Ok(())
+                } else {
+                    Err(format!("Expected '{}' but got '{}'", expected, msg))
+                }
+            }
+        )))
+        .collect::<Vec<(String, Result<(), String>)>>();
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("Example {} passed.", name),
+            Err(msg) => println!("Example {} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("Please address errors before counting anything.")
+    }
+
+    let (steps, arithmetic, mut files) = parse_args(env::args().skip(1).collect()).expect("Bad arguments.");
+    if files.len() == 0 {
+        files.push(format!("{}/input", DAY));
+    }
+    for filename in files {
+        match operation(steps, arithmetic, filename.clone()) {
+            Ok(counts) => println!("{}: {}", filename, counts),
+            Err(msg) => println!("{} failed: {}.", filename, msg)
+        }
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<(u64, Arithmetic, Vec<String>), String> {
+    let mut steps = 40;
+    let mut arithmetic = Arithmetic::Checked;
+    let mut files = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--steps" | "--modulo" => args.next()
+                .ok_or(format!("{} needs a value", arg))?
+                .parse::<u64>()
+                .map_err(|e| format!("Bad value for {}: {}", arg, e))?,
+            _ => {
+                files.push(arg);
+                continue;
+            }
+        };
+        match arg.as_str() {
+            "--steps" => steps = value,
+            _ if value == 0 => return Err(String::from("Cannot count modulo 0")),
+            _ => arithmetic = Arithmetic::Modulo(value)
+        }
+    }
+    return Ok((steps, arithmetic, files));
+}
+
+fn operation(steps: u64, arithmetic: Arithmetic, filename: String) -> Result<String, String> {
+    let (template, rules) = fs::read_to_string(filename)
+        .map_err(|io_error| format!("{}", io_error))
+        .and_then(parse)?;
+    let counts = grow(&template, &rules, steps, arithmetic)?
+        .into_iter()
+        .map(|(element, count)| format!("{}: {}", element, count))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let unruled = unruled_pairs(&template, &rules);
+    if unruled.is_empty() {
+        return Ok(counts);
+    }
+    return Ok(format!(
+        "{} (no rule for {})",
+        counts,
+        unruled.iter().map(|(l, r)| format!("{}{}", l, r)).collect::<Vec<String>>().join(", ")
+    ));
+}
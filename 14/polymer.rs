@@ -0,0 +1,213 @@
+// Shared by every day 14 program, none of which uses all of it.
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, HashMap};
+
+pub type Template = Vec<char>;
+// Each pair maps to the elements inserted between them, in order.
+pub type Rules = HashMap<(char, char), Vec<char>>;
+
+pub fn parse(contents: String) -> Result<(Template, Rules), String> {
+    let mut lines = contents.lines();
+    let template: Vec<char> = lines.next()
+        .ok_or("No lines?")?
+        .chars()
+        .collect();
+    if template.is_empty() {
+        return Err(String::from("Line 1: expected a template but the line is empty"));
+    }
+
+    match lines.next() {
+        None => return Err(String::from("No empty line")),
+        Some("") => (),
+        Some(l) => return Err(format!("Line 2: expected an empty line but found '{}'", l))
+    }
+
+    let mut insertion_rules = HashMap::new();
+    let mut first_given = HashMap::new();
+
+    for (i, l) in lines.enumerate() {
+        let line = i + 3;
+        let (pair, insertion) = parse_rule(l)
+            .map_err(|(column, msg)| format!("Line {}, column {}: {}", line, column, msg))?;
+        if let Some(first) = first_given.insert(pair, line) {
+            return Err(format!(
+                "Line {}: duplicate rule for {}{}, first given on line {}", line, pair.0, pair.1, first
+            ));
+        }
+        insertion_rules.insert(pair, insertion);
+    }
+
+    return Ok((template, insertion_rules));
+}
+
+// `XY -> Z`, where Z may be any number of elements but at least one.  Errors come with the column.
+fn parse_rule(line: &str) -> Result<((char, char), Vec<char>), (usize, String)> {
+    let chars: Vec<char> = line.chars().collect();
+    let element = |column: usize| match chars.get(column - 1) {
+        Some(c) if !c.is_whitespace() => Ok(*c),
+        Some(c) => Err((column, format!("expected an element but found '{}'", c))),
+        None => Err((column, String::from("expected an element but the line ended")))
+    };
+    let pair = (element(1)?, element(2)?);
+    if chars.len() == 2 {
+        return Err((3, String::from("expected ' -> ' but the line ended")));
+    }
+    if chars.len() < 6 || chars[2..6] != [' ', '-', '>', ' '] {
+        return Err((3, format!("expected ' -> ' but found '{}'", chars[2..].iter().collect::<String>())));
+    }
+    let insertion = (7..=chars.len().max(7))
+        .map(element)
+        .collect::<Result<Vec<char>, (usize, String)>>()?;
+    return Ok((pair, insertion));
+}
+
+// Pairs in the template, in the order they first appear, that no rule will ever insert into.
+pub fn unruled_pairs(template: &[char], rules: &Rules) -> Vec<(char, char)> {
+    let mut unruled = Vec::new();
+    for w in template.windows(2) {
+        let pair = (w[0], w[1]);
+        if !rules.contains_key(&pair) && !unruled.contains(&pair) {
+            unruled.push(pair);
+        }
+    }
+    return unruled;
+}
+
+// Counts double every step, so anything past a hundred or so steps has to be done modulo something.
+#[derive(Clone, Copy)]
+pub enum Arithmetic {
+    Checked,
+    Modulo(u64)
+}
+
+impl Arithmetic {
+    fn add(&self, a: u128, b: u128) -> Result<u128, String> {
+        return match self {
+            Arithmetic::Checked => a.checked_add(b).ok_or(overflow()),
+            Arithmetic::Modulo(m) => Ok((a + b) % *m as u128)
+        };
+    }
+
+    // Both sides are already reduced below a u64 modulus, so the product fits.
+    fn mul(&self, a: u128, b: u128) -> Result<u128, String> {
+        return match self {
+            Arithmetic::Checked => a.checked_mul(b).ok_or(overflow()),
+            Arithmetic::Modulo(m) => Ok((a * b) % *m as u128)
+        };
+    }
+
+    fn reduce(&self, a: u128) -> u128 {
+        return match self {
+            Arithmetic::Checked => a,
+            Arithmetic::Modulo(m) => a % *m as u128
+        };
+    }
+}
+
+fn overflow() -> String {
+    return String::from("Pair counts overflow a u128 - count modulo something instead");
+}
+
+type Matrix = Vec<Vec<u128>>;
+
+fn multiply(lhs: &Matrix, rhs: &Matrix, arithmetic: Arithmetic) -> Result<Matrix, String> {
+    let mut product = vec![vec![0; rhs.len()]; lhs.len()];
+    for (i, row) in lhs.iter().enumerate() {
+        for (k, a) in row.iter().enumerate().filter(|(_, a)| **a != 0) {
+            for (j, b) in rhs[k].iter().enumerate().filter(|(_, b)| **b != 0) {
+                product[i][j] = arithmetic.add(product[i][j], arithmetic.mul(*a, *b)?)?;
+            }
+        }
+    }
+    return Ok(product);
+}
+
+fn apply(matrix: &Matrix, vector: &Vec<u128>, arithmetic: Arithmetic) -> Result<Vec<u128>, String> {
+    return matrix.iter()
+        .map(|row| row.iter()
+            .zip(vector.iter())
+            .try_fold(0, |sum, (a, b)| arithmetic.add(sum, arithmetic.mul(*a, *b)?))
+        )
+        .collect();
+}
+
+// Every pair the template can ever produce, so the transition matrix only covers those.
+fn reachable_pairs(template: &[char], rules: &Rules) -> Vec<(char, char)> {
+    let mut pairs: Vec<(char, char)> = Vec::new();
+    let mut pending: Vec<(char, char)> = template.windows(2).map(|w| (w[0], w[1])).collect();
+    while let Some(pair) = pending.pop() {
+        if pairs.contains(&pair) {
+            continue;
+        }
+        if let Some(insertion) = rules.get(&pair) {
+            pending.extend(expand(pair, insertion));
+        }
+        pairs.push(pair);
+    }
+    pairs.sort();
+    return pairs;
+}
+
+// The pairs a pair becomes once its insertion is in place.
+fn expand(pair: (char, char), insertion: &[char]) -> Vec<(char, char)> {
+    let mut elements = vec![pair.0];
+    elements.extend_from_slice(insertion);
+    elements.push(pair.1);
+    return elements.windows(2).map(|w| (w[0], w[1])).collect();
+}
+
+// Element counts after `steps` insertions, by element.  Only pairs are tracked: every element is
+// the first of exactly one pair except the last, which never moves.  One step maps each pair's
+// count onto the pairs it becomes, so `steps` steps are a matrix power, done by squaring.
+pub fn grow(template: &[char], rules: &Rules, steps: u64, arithmetic: Arithmetic) -> Result<Vec<(char, u128)>, String> {
+    let last = *template.last().ok_or("Empty template")?;
+    let pairs = reachable_pairs(template, rules);
+    let index: HashMap<(char, char), usize> = pairs.iter()
+        .enumerate()
+        .map(|(i, pair)| (*pair, i))
+        .collect();
+
+    let mut transitions: Matrix = vec![vec![0; pairs.len()]; pairs.len()];
+    for (from, pair) in pairs.iter().enumerate() {
+        match rules.get(pair) {
+            Some(insertion) => for to in expand(*pair, insertion) {
+                transitions[index[&to]][from] += 1;
+            },
+            None => transitions[from][from] += 1
+        }
+    }
+
+    let mut counts = vec![0; pairs.len()];
+    for w in template.windows(2) {
+        let i = index[&(w[0], w[1])];
+        counts[i] = arithmetic.add(counts[i], 1)?;
+    }
+
+    let mut remaining = steps;
+    while remaining > 0 {
+        if remaining % 2 == 1 {
+            counts = apply(&transitions, &counts, arithmetic)?;
+        }
+        remaining /= 2;
+        if remaining > 0 {
+            transitions = multiply(&transitions, &transitions, arithmetic)?;
+        }
+    }
+
+    let mut elements = BTreeMap::new();
+    elements.insert(last, arithmetic.reduce(1));
+    for ((first, _), count) in pairs.iter().zip(counts.iter()) {
+        let total = arithmetic.add(*elements.get(first).unwrap_or(&0), *count)?;
+        elements.insert(*first, total);
+    }
+    // Modulo something, zero doesn't mean absent.
+    return Ok(
+        elements.into_iter()
+            .filter(|(_, count)| *count > 0 || match arithmetic {
+                Arithmetic::Checked => false,
+                Arithmetic::Modulo(_) => true
+            })
+            .collect()
+    );
+}
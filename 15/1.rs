@@ -1,10 +1,9 @@
 use std::fs;
-use std::collections::BinaryHeap;
-use std::collections::HashSet;
-use std::cmp::Ordering;
-use std::cmp::Reverse;
 
-type ParseTarget = Vec<Vec<u32>>;
+mod chiton;
+use chiton::{Cave, Moves, lowest_risk_path, parse_cave};
+
+type ParseTarget = Cave;
 type Solution = u32;
 
 const EXAMPLES: [(&str, Solution); 1] = [
@@ -13,6 +12,8 @@ const EXAMPLES: [(&str, Solution); 1] = [
 
 const DAY: u8 = 15;
 
+const TILES: usize = 1;
+
 fn main() {
     let results = EXAMPLES.iter()
         .zip(
@@ -48,9 +49,9 @@ fn main() {
     );
 }
 
-fn error<T>(msg: &str) -> Result<T, String> {
-    return Err(String::from(msg));
-}
+//fn error<T>(msg: &str) -> Result<T, String> {
+//    return Err(String::from(msg));
+//}
 
 fn operation(filename: String) -> Result<Solution, String> {
     return fs::read_to_string(filename)
@@ -60,109 +61,10 @@ fn operation(filename: String) -> Result<Solution, String> {
 }
 
 fn parse(contents: String) -> Result<ParseTarget, String> {
-    return contents.lines()
-        .map(|l| l.chars()
-            .map(|c| c.to_digit(10).ok_or(format!("{} is not a digit.", c)))
-            .collect::<Result<Vec<u32>, String>>()
-        ).collect::<Result<Vec<Vec<u32>>, String>>();
-}
-
-struct Position {
-    loc: (usize, usize),
-    risk: u32,
-    target: (usize, usize)
+    return parse_cave(&contents, TILES);
 }
 
-impl Position {
-    fn new(loc: (usize, usize), risk: u32, target: (usize, usize)) -> Position {
-        return Position {
-            loc:loc, risk: risk, target: target
-        };
-    }
-    fn min_remaining(&self) -> usize{
-        let (tx, ty) = self.target;
-        let (x, y) = self.loc;
-
-        return (tx - x) + (ty - y);
-    }
-    fn up(&self, costs: &Vec<Vec<u32>>) -> Position {
-        let (old_y, x) = self.loc;
-        let y = old_y - 1;
-
-        return Position::new((y, x), self.risk + costs[y][x], self.target);
-    }
-    fn down(&self, costs: &Vec<Vec<u32>>) -> Position {
-        let (old_y, x) = self.loc;
-        let y = old_y + 1;
-
-        return Position::new((y, x), self.risk + costs[y][x], self.target);
-    }
-    fn left(&self, costs: &Vec<Vec<u32>>) -> Position {
-        let (y, old_x) = self.loc;
-        let x = old_x - 1;
-
-        return Position::new((y, x), self.risk + costs[y][x], self.target);
-    }
-    fn right(&self, costs: &Vec<Vec<u32>>) -> Position {
-        let (y, old_x) = self.loc;
-        let x = old_x + 1;
-
-        return Position::new((y, x), self.risk + costs[y][x], self.target);
-    }
-}
-
-impl Ord for Position {
-    fn cmp(&self, other: &Self) -> Ordering {
-        return (Reverse(self.risk), Reverse(self.min_remaining()))
-            .cmp(&(Reverse(other.risk), (Reverse(other.min_remaining()))))
-    }
-}
-
-impl PartialOrd for Position {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        return Some(self.cmp(other));
-    }
-}
-
-impl PartialEq for Position {
-    fn  eq(&self, other: &Self) -> bool {
-        return self.risk == other.risk && self.min_remaining() == other.min_remaining();
-    }
-}
-
-impl Eq for Position {}
-
 fn solve(parsed: ParseTarget) -> Result<Solution, String> {
-    let target_y = parsed.len() - 1;
-    let target_x = parsed[target_y].len() - 1;
-
-    let start = Position::new((0, 0), 0, (target_y, target_x));
-    let mut positions = BinaryHeap::new();
-    let mut seen = HashSet::new();
-    positions.push(start);
-
-    while let Some(curr) = positions.pop() {
-        if curr.min_remaining() == 0 {
-            return Ok(curr.risk);
-        }
-        // Risk is monotonically increasing, so if we've seen a position before, it's because 
-        // there's a way to get there with lower (or equivalent) risk.
-        if !seen.insert(curr.loc) {
-            continue;
-        }
-        if curr.loc.0 > 0 {
-            positions.push(curr.up(&parsed));
-        }
-        if curr.loc.0 < target_y {
-            positions.push(curr.down(&parsed));
-        }
-        if curr.loc.1 > 0 {
-            positions.push(curr.left(&parsed));
-        }
-        if curr.loc.1 < target_x {
-            positions.push(curr.right(&parsed));
-        }
-    }
-    
-    return error("Could Not find a Path");
+    let goal = (parsed.height() - 1, parsed.width() - 1);
+    return lowest_risk_path(&parsed, Moves::Orthogonal, (0, 0), goal).map(|(risk, _)| risk);
 }
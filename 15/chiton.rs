@@ -0,0 +1,314 @@
+// Shared by every day 15 program, none of which uses all of it.
+#![allow(dead_code)]
+
+use std::collections::{BinaryHeap, HashSet};
+use std::cmp::Reverse;
+use std::str::FromStr;
+
+pub type Grid = Vec<Vec<u32>>;
+
+// The base tile repeated `tiles` times in each direction, every repeat right or down one risk
+// higher than the last (wrapping from 9 back to 1).  Risks are worked out when asked for.
+pub struct Cave {
+    tile: Grid,
+    tiles: usize
+}
+
+impl Cave {
+    pub fn new(tile: Grid, tiles: usize) -> Result<Cave, String> {
+        if tile.is_empty() || tile[0].is_empty() {
+            return Err(String::from("Empty map"));
+        }
+        if tile.iter().any(|row| row.len() != tile[0].len()) {
+            return Err(String::from("Map is not rectangular"));
+        }
+        if tiles == 0 {
+            return Err(String::from("Need at least one tile"));
+        }
+        return Ok(Cave { tile: tile, tiles: tiles });
+    }
+
+    pub fn height(&self) -> usize {
+        return self.tile.len() * self.tiles;
+    }
+
+    pub fn width(&self) -> usize {
+        return self.tile[0].len() * self.tiles;
+    }
+
+    pub fn risk(&self, (y, x): (usize, usize)) -> u32 {
+        let (h, w) = (self.tile.len(), self.tile[0].len());
+        return inc_risk(self.tile[y % h][x % w], (y / h + x / w) as u32);
+    }
+
+    // The lowest risk anywhere in the cave.  Past eight tiles' worth of offset it all repeats.
+    fn cheapest(&self) -> u32 {
+        return (0..(2 * self.tiles - 1).min(9) as u32)
+            .flat_map(|offset| self.tile.iter()
+                .flat_map(move |row| row.iter().map(move |r| inc_risk(*r, offset)))
+            )
+            .min()
+            .unwrap_or(0);
+    }
+}
+
+fn inc_risk(risk: u32, offset: u32) -> u32 {
+    let new = risk + offset;
+    if new > 9 {
+        return (new - 1) % 9 + 1;
+    } else {
+        return new;
+    }
+}
+
+pub fn parse_cave(contents: &str, tiles: usize) -> Result<Cave, String> {
+    let tile = contents.lines()
+        .map(|l| l.chars()
+            .map(|c| c.to_digit(10).ok_or(format!("{} is not a digit.", c)))
+            .collect::<Result<Vec<u32>, String>>()
+        ).collect::<Result<Vec<Vec<u32>>, String>>()?;
+    return Cave::new(tile, tiles);
+}
+
+// How a path may move from one position to the next.
+#[derive(Clone, Copy)]
+pub enum Moves {
+    Orthogonal,
+    King,
+    Knight
+}
+
+const ORTHOGONAL: [(i64, i64); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const KING: [(i64, i64); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+const KNIGHT: [(i64, i64); 8] = [(-2, -1), (-2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2), (2, -1), (2, 1)];
+
+impl Moves {
+    pub fn offsets(&self) -> &'static [(i64, i64)] {
+        return match self {
+            Moves::Orthogonal => &ORTHOGONAL,
+            Moves::King => &KING,
+            Moves::Knight => &KNIGHT
+        };
+    }
+
+    // The fewest moves it could possibly take to cover a distance, ignoring the edges of the cave.
+    fn fewest(&self, dy: usize, dx: usize) -> usize {
+        return match self {
+            Moves::Orthogonal => dy + dx,
+            Moves::King => dy.max(dx),
+            Moves::Knight => ((dy + 1) / 2).max((dx + 1) / 2).max((dy + dx + 2) / 3)
+        };
+    }
+}
+
+impl FromStr for Moves {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Moves, String> {
+        return match s {
+            "4" => Ok(Moves::Orthogonal),
+            "8" => Ok(Moves::King),
+            "knight" => Ok(Moves::Knight),
+            _ => Err(format!("Unknown moves '{}' - try 4, 8 or knight", s))
+        };
+    }
+}
+
+// Where a position leads: each neighbour with the move that gets there, counting from one so
+// that zero can mean the start.
+fn neighbours(cave: &Cave, moves: Moves, (y, x): (usize, usize)) -> Vec<((usize, usize), u8)> {
+    return moves.offsets()
+        .iter()
+        .enumerate()
+        .map(|(i, (dy, dx))| ((y as i64 + dy, x as i64 + dx), i as u8 + 1))
+        .filter(|((y, x), _)|
+            *y >= 0 && *x >= 0 && (*y as usize) < cave.height() && (*x as usize) < cave.width()
+        )
+        .map(|((y, x), i)| ((y as usize, x as usize), i))
+        .collect();
+}
+
+fn check_inside(cave: &Cave, (y, x): (usize, usize)) -> Result<(), String> {
+    if y >= cave.height() || x >= cave.width() {
+        return Err(format!("({}, {}) is outside the cave", y, x));
+    }
+    return Ok(());
+}
+
+// Positions and moves a search may not use, so that paths can be found around other paths.
+struct Banned {
+    positions: HashSet<(usize, usize)>,
+    steps: HashSet<((usize, usize), (usize, usize))>
+}
+
+impl Banned {
+    fn nothing() -> Banned {
+        return Banned { positions: HashSet::new(), steps: HashSet::new() };
+    }
+}
+
+// The lowest risk of reaching each position (as far as it had to look) and the move that got
+// there.  With a goal it's A*, stopping once the goal is reached: every move costs at least the
+// lowest risk in the cave, so that times the fewest moves left never overestimates what's left.
+// Without one it's plain Dijkstra over the whole cave.  Everything is kept in flat arrays - a big
+// enough tiling doesn't leave room for much else.
+fn search(
+    cave: &Cave,
+    moves: Moves,
+    start: (usize, usize),
+    goal: Option<(usize, usize)>,
+    banned: &Banned
+) -> (Vec<u32>, Vec<u8>) {
+    let width = cave.width();
+    let cheapest = cave.cheapest();
+    let heuristic = |(y, x): (usize, usize)| match goal {
+        Some((gy, gx)) => cheapest * moves.fewest(
+            (gy as i64 - y as i64).abs() as usize,
+            (gx as i64 - x as i64).abs() as usize
+        ) as u32,
+        None => 0
+    };
+
+    let mut risks = vec![u32::MAX; cave.height() * width];
+    let mut previous = vec![0; cave.height() * width];
+    let mut positions = BinaryHeap::new();
+    risks[start.0 * width + start.1] = 0;
+    positions.push(Reverse((heuristic(start), 0, start)));
+
+    while let Some(Reverse((_, risk, loc))) = positions.pop() {
+        // A cheaper way here was found after this one was queued.
+        if risk > risks[loc.0 * width + loc.1] {
+            continue;
+        }
+        if Some(loc) == goal {
+            break;
+        }
+        for (next, from) in neighbours(cave, moves, loc) {
+            if banned.positions.contains(&next) || banned.steps.contains(&(loc, next)) {
+                continue;
+            }
+            let next_risk = risk + cave.risk(next);
+            let i = next.0 * width + next.1;
+            if next_risk < risks[i] {
+                risks[i] = next_risk;
+                previous[i] = from;
+                positions.push(Reverse((next_risk + heuristic(next), next_risk, next)));
+            }
+        }
+    }
+    return (risks, previous);
+}
+
+// Follows the moves back from the goal to wherever the search started.
+fn path_to(cave: &Cave, moves: Moves, previous: &Vec<u8>, goal: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut path = vec![goal];
+    let (mut y, mut x) = goal;
+    while previous[y * cave.width() + x] > 0 {
+        let (dy, dx) = moves.offsets()[previous[y * cave.width() + x] as usize - 1];
+        y = (y as i64 - dy) as usize;
+        x = (x as i64 - dx) as usize;
+        path.push((y, x));
+    }
+    path.reverse();
+    return path;
+}
+
+fn find_path(
+    cave: &Cave,
+    moves: Moves,
+    start: (usize, usize),
+    goal: (usize, usize),
+    banned: &Banned
+) -> Option<(u32, Vec<(usize, usize)>)> {
+    let (risks, previous) = search(cave, moves, start, Some(goal), banned);
+    let risk = risks[goal.0 * cave.width() + goal.1];
+    if risk == u32::MAX {
+        return None;
+    }
+    return Some((risk, path_to(cave, moves, &previous, goal)));
+}
+
+// The total risk and every position on the way, start and goal included.  The start's own risk
+// doesn't count.
+pub fn lowest_risk_path(
+    cave: &Cave,
+    moves: Moves,
+    start: (usize, usize),
+    goal: (usize, usize)
+) -> Result<(u32, Vec<(usize, usize)>), String> {
+    check_inside(cave, start)?;
+    check_inside(cave, goal)?;
+    return find_path(cave, moves, start, goal, &Banned::nothing())
+        .ok_or(String::from("Could Not find a Path"));
+}
+
+// The lowest risk of reaching every position from the start, if it can be reached at all.
+pub fn risk_map(cave: &Cave, moves: Moves, start: (usize, usize)) -> Result<Vec<Vec<Option<u32>>>, String> {
+    check_inside(cave, start)?;
+    let (risks, _) = search(cave, moves, start, None, &Banned::nothing());
+    return Ok(
+        risks.chunks(cave.width())
+            .map(|row| row.iter().map(|r| if *r == u32::MAX { None } else { Some(*r) }).collect())
+            .collect()
+    );
+}
+
+pub fn path_risk(cave: &Cave, path: &[(usize, usize)]) -> u32 {
+    return path.iter().skip(1).map(|loc| cave.risk(*loc)).sum();
+}
+
+// Up to `k` paths that never visit a position twice, lowest risk first, by Yen's algorithm.  Each
+// new path follows one already found part of the way, then takes the best route to the goal that
+// neither doubles back over the shared part nor leaves it the same way any found path did.
+pub fn lowest_risk_paths(
+    cave: &Cave,
+    moves: Moves,
+    start: (usize, usize),
+    goal: (usize, usize),
+    k: usize
+) -> Result<Vec<(u32, Vec<(usize, usize)>)>, String> {
+    check_inside(cave, start)?;
+    check_inside(cave, goal)?;
+    if k == 0 {
+        return Ok(Vec::new());
+    }
+    let mut found = vec![lowest_risk_path(cave, moves, start, goal)?];
+    let mut candidates: Vec<(u32, Vec<(usize, usize)>)> = Vec::new();
+    while found.len() < k {
+        let last = found[found.len() - 1].1.clone();
+        for i in 0..last.len() - 1 {
+            let root = &last[..=i];
+            let banned = Banned {
+                positions: root[..i].iter().cloned().collect(),
+                steps: found.iter()
+                    .map(|(_, path)| path)
+                    .filter(|path| path.len() > i + 1 && path[..=i] == *root)
+                    .map(|path| (path[i], path[i + 1]))
+                    .collect()
+            };
+            if let Some((_, spur)) = find_path(cave, moves, last[i], goal, &banned) {
+                let path = root[..i].iter().chain(spur.iter()).cloned().collect::<Vec<(usize, usize)>>();
+                if !candidates.iter().chain(found.iter()).any(|(_, p)| *p == path) {
+                    candidates.push((path_risk(cave, &path), path));
+                }
+            }
+        }
+        let best = match candidates.iter().enumerate().min_by_key(|(_, (risk, _))| *risk) {
+            Some((i, _)) => i,
+            None => break
+        };
+        found.push(candidates.swap_remove(best));
+    }
+    return Ok(found);
+}
+
+// The cave with everything off the path blanked out.
+pub fn render(cave: &Cave, path: &[(usize, usize)]) -> String {
+    let mut canvas = vec![vec!['.'; cave.width()]; cave.height()];
+    for loc in path.iter() {
+        canvas[loc.0][loc.1] = std::char::from_digit(cave.risk(*loc), 10).unwrap_or('?');
+    }
+    return canvas.into_iter()
+        .map(|line| line.into_iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n");
+}
@@ -0,0 +1,268 @@
+use std::env;
+use std::fs;
+use std::collections::HashSet;
+
+mod chiton;
+use chiton::{Cave, Moves, lowest_risk_path, lowest_risk_paths, parse_cave, path_risk, render, risk_map};
+
+const DAY: u8 = 15;
+
+// Maps with only one lowest-risk path between two positions, its risk, and how it renders.
+const EXAMPLES: [(&str, Moves, (usize, usize), (usize, usize), u32, &str); 6] = [
+    ("19\n11", Moves::Orthogonal, (0, 0), (1, 1), 2, "1.\n11"),
+    (
+        "1111\n9991\n1111\n1999\n1111",
+        Moves::Orthogonal,
+        (0, 0),
+        (4, 3),
+        13,
+        "1111\n...1\n1111\n1...\n1111"
+    ),
+    ("1099\n0990\n0990\n0000", Moves::Orthogonal, (0, 0), (3, 3), 0, "1...\n0...\n0...\n0000"),
+    ("19\n11", Moves::Orthogonal, (1, 1), (0, 0), 2, "1.\n11"),
+    ("19\n11", Moves::King, (0, 0), (1, 1), 1, "1.\n.1"),
+    ("191\n111\n111", Moves::Knight, (0, 0), (2, 2), 4, "1.1\n1..\n.11")
+];
+
+// Paths that can't be found, and why.
+const IMPOSSIBLE: [(&str, Moves, (usize, usize), (usize, usize), &str); 2] = [
+    ("11\n11", Moves::Knight, (0, 0), (1, 1), "Could Not find a Path"),
+    ("11\n11", Moves::Orthogonal, (0, 0), (2, 0), "(2, 0) is outside the cave")
+];
+
+// Maps with ties, how many tiles to repeat them over, and the lowest risk through them.  Any path
+// will do as long as it adds up.
+const TIED: [(&str, usize, u32); 3] = [
+    ("1", 1, 40),
+    ("1", 5, 315),
+    ("1", 50, 3075)
+];
+
+// The lowest risk of reaching every position from a start, '-' where there's no way there.
+const RISK_MAPS: [(&str, Moves, (usize, usize), &str); 3] = [
+    ("19\n11", Moves::Orthogonal, (0, 0), "0 9\n1 2"),
+    ("11\n11", Moves::Knight, (0, 0), "0 -\n- -"),
+    ("123\n456", Moves::King, (1, 1), "1 2 3\n4 0 6")
+];
+
+// How many paths to ask for from corner to corner, and the risks of those found.
+const BEST: [(&str, usize, &[u32]); 3] = [
+    ("131\n212\n121", 20, &[6, 6, 6, 7, 7, 7, 9, 10, 10, 10, 13, 13]),
+    ("131\n212\n121", 0, &[]),
+    ("1163\n1381\n2136", 10, &[13, 14, 14, 17, 17, 19, 19, 21, 21, 21])
+];
+
+struct Options {
+    tiles: usize,
+    moves: Moves,
+    start: (usize, usize),
+    goal: Option<(usize, usize)>,
+    best: usize,
+    risks: bool,
+    files: Vec<String>
+}
+
+// Usage: path [--tiles N] [--moves 4|8|knight] [--from Y,X] [--to Y,X] [--best K] [--risks] [map files...]
+// Defaults to one tile of the puzzle input, moving orthogonally from the top left to the bottom
+// right.  `--best` draws the K lowest-risk paths, `--risks` the lowest risk to every position.
+fn main() {
+    let results = EXAMPLES.iter()
+        .map(|(contents, moves, start, goal, risk, expected)| (
+            format!("{:?}", contents),
+            parse_cave(contents, 1)
+                .and_then(|cave| {
+                    let (actual, path) = lowest_risk_path(&cave, *moves, *start, *goal)?;
+                    check(&cave, *moves, *start, *goal, actual, &path)?;
+                    if actual != *risk {
+                        return Err(format!("Expected risk {} but got {}", risk, actual));
+                    }
+                    return Ok(render(&cave, &path));
+                })
+                .and_then(|actual| if *expected == actual {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected\n{}\nbut got\n{}\n", expected, actual));
+                })
+        ))
+        .chain(IMPOSSIBLE.iter().map(|(contents, moves, start, goal, expected)| (
+            format!("{:?}", contents),
+            parse_cave(contents, 1)
+                .and_then(|cave| match lowest_risk_path(&cave, *moves, *start, *goal) {
+                    Ok((risk, _)) => Err(format!("Expected '{}' but found a path of risk {}", expected, risk)),
+                    Err(msg) => if *expected == msg {
+                        Ok(())
+                    } else {
+                        Err(format!("Expected '{}' but got '{}'", expected, msg))
+                    }
+                })
+        )))
+        .chain(TIED.iter().map(|(name, tiles, risk)| (
+            format!("{} ({}x{} tiles)", name, tiles, tiles),
+            fs::read_to_string(format!("{}/{}.ie", DAY, name))
+                .map_err(|io_error| format!("{}", io_error))
+                .and_then(|contents| parse_cave(&contents, *tiles))
+                .and_then(|cave| {
+                    let goal = (cave.height() - 1, cave.width() - 1);
+                    let (actual, path) = lowest_risk_path(&cave, Moves::Orthogonal, (0, 0), goal)?;
+                    check(&cave, Moves::Orthogonal, (0, 0), goal, actual, &path)?;
+                    if actual != *risk {
+                        return Err(format!("Expected risk {} but got {}", risk, actual));
+                    }
+                    return Ok(());
+                })
+        )))
+        .chain(RISK_MAPS.iter().map(|(contents, moves, start, expected)| (
+            format!("{:?} risks", contents),
+            parse_cave(contents, 1)
+                .and_then(|cave| risk_map(&cave, *moves, *start))
+                .map(|risks| render_risks(&risks))
+                .and_then(|actual| if *expected == actual {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected\n{}\nbut got\n{}\n", expected, actual));
+                })
+        )))
+        .chain(BEST.iter().map(|(contents, k, expected)| (
+            format!("{:?} best {}", contents, k),
+            parse_cave(contents, 1).and_then(|cave| check_best(&cave, *k, expected))
+        )))
+        .collect::<Vec<(String, Result<(), String>)>>();
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("Example {} passed.", name),
+            Err(msg) => println!("Example {} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("Please address errors before drawing any paths.")
+    }
+
+    let mut options = parse_args(env::args().skip(1).collect()).expect("Bad arguments.");
+    if options.files.len() == 0 {
+        options.files.push(format!("{}/input", DAY));
+    }
+    for filename in options.files.iter() {
+        let result = fs::read_to_string(&filename)
+            .map_err(|io_error| format!("{}", io_error))
+            .and_then(|contents| parse_cave(&contents, options.tiles))
+            .and_then(|cave| draw(&cave, &options));
+        match result {
+            Ok(drawn) => println!("{}: {}", filename, drawn),
+            Err(msg) => println!("{} failed: {}.", filename, msg)
+        }
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut options = Options {
+        tiles: 1,
+        moves: Moves::Orthogonal,
+        start: (0, 0),
+        goal: None,
+        best: 1,
+        risks: false,
+        files: Vec::new()
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tiles" | "--moves" | "--from" | "--to" | "--best" => (),
+            "--risks" => {
+                options.risks = true;
+                continue;
+            },
+            _ => {
+                options.files.push(arg);
+                continue;
+            }
+        }
+        let value = args.next().ok_or(format!("{} needs a value", arg))?;
+        let bad = |e: String| format!("Bad value for {}: {}", arg, e);
+        match arg.as_str() {
+            "--tiles" => options.tiles = value.parse().map_err(|e| bad(format!("{}", e)))?,
+            "--moves" => options.moves = value.parse().map_err(bad)?,
+            "--from" => options.start = parse_position(&value).map_err(bad)?,
+            "--to" => options.goal = Some(parse_position(&value).map_err(bad)?),
+            _ => options.best = value.parse().map_err(|e| bad(format!("{}", e)))?
+        }
+    }
+    return Ok(options);
+}
+
+fn parse_position(s: &str) -> Result<(usize, usize), String> {
+    let mut parts = s.split(",").map(|n| n.parse::<usize>().map_err(|e| format!("{}", e)));
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(y), Some(x), None) => return Ok((y?, x?)),
+        _ => return Err(format!("Expected Y,X but found '{}'", s))
+    }
+}
+
+fn draw(cave: &Cave, options: &Options) -> Result<String, String> {
+    if options.risks {
+        return risk_map(cave, options.moves, options.start).map(|risks| format!("\n{}", render_risks(&risks)));
+    }
+    let goal = options.goal.unwrap_or((cave.height() - 1, cave.width() - 1));
+    return Ok(
+        lowest_risk_paths(cave, options.moves, options.start, goal, options.best)?
+            .into_iter()
+            .map(|(risk, path)| format!("risk {}\n{}", risk, render(cave, &path)))
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    );
+}
+
+fn render_risks(risks: &Vec<Vec<Option<u32>>>) -> String {
+    return risks.iter()
+        .map(|row| row.iter()
+            .map(|risk| risk.map(|r| r.to_string()).unwrap_or(String::from("-")))
+            .collect::<Vec<String>>()
+            .join(" ")
+        )
+        .collect::<Vec<String>>()
+        .join("\n");
+}
+
+// Makes sure a path really goes from start to goal by legal moves, costing what it should.
+fn check(
+    cave: &Cave,
+    moves: Moves,
+    start: (usize, usize),
+    goal: (usize, usize),
+    risk: u32,
+    path: &[(usize, usize)]
+) -> Result<(), String> {
+    if path.first() != Some(&start) || path.last() != Some(&goal) {
+        return Err(format!("Path runs from {:?} to {:?}", path.first(), path.last()));
+    }
+    for step in path.windows(2) {
+        let ((y1, x1), (y2, x2)) = (step[0], step[1]);
+        let offset = (y2 as i64 - y1 as i64, x2 as i64 - x1 as i64);
+        if !moves.offsets().contains(&offset) {
+            return Err(format!("{:?} to {:?} is not a legal move", step[0], step[1]));
+        }
+    }
+    if path.iter().collect::<HashSet<&(usize, usize)>>().len() != path.len() {
+        return Err(String::from("Path visits a position twice"));
+    }
+    let total = path_risk(cave, path);
+    if total != risk {
+        return Err(format!("Path adds up to {} but the risk was {}", total, risk));
+    }
+    return Ok(());
+}
+
+fn check_best(cave: &Cave, k: usize, expected: &[u32]) -> Result<(), String> {
+    let goal = (cave.height() - 1, cave.width() - 1);
+    let paths = lowest_risk_paths(cave, Moves::Orthogonal, (0, 0), goal, k)?;
+    for (risk, path) in paths.iter() {
+        check(cave, Moves::Orthogonal, (0, 0), goal, *risk, path)?;
+    }
+    if paths.iter().map(|(_, path)| path).collect::<HashSet<&Vec<(usize, usize)>>>().len() != paths.len() {
+        return Err(String::from("The same path was found twice"));
+    }
+    let risks = paths.iter().map(|(risk, _)| *risk).collect::<Vec<u32>>();
+    if risks != expected {
+        return Err(format!("Expected risks {:?} but got {:?}", expected, risks));
+    }
+    return Ok(());
+}
@@ -0,0 +1,745 @@
+// Shared by every day 16 program, none of which uses all of it.
+#![allow(dead_code)]
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter;
+
+pub struct BitReader {
+    bytes: Vec<u8>,
+    length: usize,
+    position: usize
+}
+
+impl BitReader {
+    pub fn from_hex(hex: &str) -> Result<BitReader, String> {
+        let mut bytes = Vec::with_capacity((hex.len() + 1) / 2);
+        for (i, c) in hex.chars().enumerate() {
+            let nibble = match c {
+                '0'..='9' => c as u8 - b'0',
+                'A'..='F' => c as u8 - b'A' + 10,
+                _ => return Err(format!("Unrecognized Hex Digit '{}' at bit {}", c, i * 4))
+            };
+            if i % 2 == 0 {
+                bytes.push(nibble << 4);
+            } else {
+                *bytes.last_mut().unwrap() |= nibble;
+            }
+        }
+        let length = hex.len() * 4;
+        return Ok(BitReader {
+            bytes: bytes,
+            length: length,
+            position: 0
+        });
+    }
+
+    pub fn position(&self) -> usize {
+        return self.position;
+    }
+
+    pub fn remaining(&self) -> usize {
+        return self.length - self.position;
+    }
+
+    // `field` names what is being read, for error messages.
+    pub fn read_bits(&mut self, count: usize, field: &str) -> Result<u64, String> {
+        if count > 64 {
+            return Err(format!("Cannot read {} bits of {} into a u64 at bit {}", count, field, self.position));
+        }
+        if count > self.remaining() {
+            return Err(format!(
+                "Ran out of input reading {} at bit {}: needed {} bits, only {} left",
+                field, self.position, count, self.remaining()
+            ));
+        }
+        let mut value = 0u64;
+        let mut needed = count;
+        while needed > 0 {
+            let byte = self.bytes[self.position / 8];
+            let available = 8 - self.position % 8;
+            let taken = if needed < available { needed } else { available };
+            let bits = (byte >> (available - taken)) & (((1u16 << taken) - 1) as u8);
+            value = (value << taken) | bits as u64;
+            self.position += taken;
+            needed -= taken;
+        }
+        return Ok(value);
+    }
+}
+
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    length: usize
+}
+
+impl BitWriter {
+    pub fn new() -> BitWriter {
+        return BitWriter {
+            bytes: Vec::new(),
+            length: 0
+        };
+    }
+
+    pub fn position(&self) -> usize {
+        return self.length;
+    }
+
+    pub fn write_bits(&mut self, value: u64, count: usize) {
+        for i in (0..count).rev() {
+            if self.length % 8 == 0 {
+                self.bytes.push(0);
+            }
+            self.length += 1;
+            self.set_bit(self.length - 1, (value >> i) & 1 == 1);
+        }
+    }
+
+    // Overwrites bits that were already written, for lengths only known after the fact.
+    pub fn patch_bits(&mut self, position: usize, value: u64, count: usize) {
+        for i in 0..count {
+            self.set_bit(position + i, (value >> (count - 1 - i)) & 1 == 1);
+        }
+    }
+
+    fn set_bit(&mut self, position: usize, bit: bool) {
+        let mask = 0x80 >> (position % 8);
+        if bit {
+            self.bytes[position / 8] |= mask;
+        } else {
+            self.bytes[position / 8] &= !mask;
+        }
+    }
+
+    pub fn to_hex(&self) -> String {
+        let digits = (self.length + 3) / 4;
+        return (0..digits)
+            .map(|i| (self.bytes[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0xF)
+            .map(|nibble| std::char::from_digit(nibble as u32, 16).unwrap().to_ascii_uppercase())
+            .collect();
+    }
+}
+
+// Literals may have any number of groups, so they are kept at full precision.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BigUint {
+    // Little-endian base 2^32, without leading zero limbs.
+    limbs: Vec<u32>
+}
+
+impl BigUint {
+    pub fn from_u64(value: u64) -> BigUint {
+        return BigUint { limbs: vec![value as u32, (value >> 32) as u32] }.trim();
+    }
+
+    // Most significant nibble first.
+    pub fn from_nibbles(nibbles: &[u8]) -> BigUint {
+        let limbs = nibbles.rchunks(8)
+            .map(|chunk| chunk.iter().fold(0u32, |acc, n| (acc << 4) | *n as u32))
+            .collect();
+        return BigUint { limbs: limbs }.trim();
+    }
+
+    pub fn parse_decimal(digits: &str) -> Option<BigUint> {
+        if digits.len() == 0 {
+            return None;
+        }
+        let mut value = BigUint { limbs: Vec::new() };
+        for c in digits.chars() {
+            value = value.times_small_plus(10, c.to_digit(10)?);
+        }
+        return Some(value);
+    }
+
+    fn trim(mut self) -> BigUint {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        return self;
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        return match self.limbs.len() {
+            0 => Some(0),
+            1 => Some(self.limbs[0] as u64),
+            2 => Some(self.limbs[0] as u64 | (self.limbs[1] as u64) << 32),
+            _ => None
+        };
+    }
+
+    // Most significant nibble first, with no leading zeros beyond the first.
+    pub fn nibbles(&self) -> Vec<u8> {
+        let nibbles = self.limbs.iter().rev()
+            .flat_map(|limb| (0..8).rev().map(move |i| ((limb >> (4 * i)) & 0xF) as u8))
+            .skip_while(|n| *n == 0)
+            .collect::<Vec<u8>>();
+        return if nibbles.len() == 0 { vec![0] } else { nibbles };
+    }
+
+    pub fn plus(&self, other: &BigUint) -> BigUint {
+        let mut limbs = Vec::new();
+        let mut carry = 0u64;
+        for i in 0..self.limbs.len().max(other.limbs.len()) {
+            let sum = carry
+                + *self.limbs.get(i).unwrap_or(&0) as u64
+                + *other.limbs.get(i).unwrap_or(&0) as u64;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+        return BigUint { limbs: limbs }.trim();
+    }
+
+    pub fn times(&self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, l) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, r) in other.limbs.iter().enumerate() {
+                let product = limbs[i + j] as u64 + (*l as u64) * (*r as u64) + carry;
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        return BigUint { limbs: limbs }.trim();
+    }
+
+    fn times_small_plus(&self, factor: u32, addend: u32) -> BigUint {
+        let mut limbs = Vec::new();
+        let mut carry = addend as u64;
+        for limb in self.limbs.iter() {
+            let product = (*limb as u64) * (factor as u64) + carry;
+            limbs.push(product as u32);
+            carry = product >> 32;
+        }
+        limbs.push(carry as u32);
+        return BigUint { limbs: limbs }.trim();
+    }
+
+    fn divide_small(&self, divisor: u32) -> (BigUint, u32) {
+        let mut limbs = vec![0u32; self.limbs.len()];
+        let mut remainder = 0u64;
+        for i in (0..self.limbs.len()).rev() {
+            let current = (remainder << 32) | self.limbs[i] as u64;
+            limbs[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        return (BigUint { limbs: limbs }.trim(), remainder as u32);
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return self.limbs.len().cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()));
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while rest.limbs.len() > 0 {
+            let (quotient, remainder) = rest.divide_small(1_000_000_000);
+            chunks.push(remainder);
+            rest = quotient;
+        }
+        return match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, others)) => {
+                write!(f, "{}", first)?;
+                others.iter().rev().map(|c| write!(f, "{:09}", c)).collect()
+            }
+        };
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Packet {
+    Operator(u8, u8, Vec<Packet>),
+    Value(u8, u8, BigUint)
+}
+
+pub enum Body {
+    Literal(BigUint),
+    Operator(LengthType, u64)
+}
+
+// Where a packet sits in the transmission, for disassembly.
+pub struct Trace {
+    pub offset: usize,
+    pub depth: usize,
+    pub version: u8,
+    pub type_id: u8,
+    pub body: Body,
+    pub body_offset: usize,
+    pub end: usize
+}
+
+// Parses the outermost packet, which must be followed only by zero padding.
+pub fn parse_packet(reader: &mut BitReader) -> Result<Packet, String> {
+    let packet = read_packet(reader, None)?;
+    expect_padding(reader)?;
+    return Ok(packet);
+}
+
+pub fn trace_packet(reader: &mut BitReader) -> Result<(Packet, Vec<Trace>), String> {
+    let mut traces = Vec::new();
+    let packet = read_packet(reader, Some(&mut traces))?;
+    expect_padding(reader)?;
+    return Ok((packet, traces));
+}
+
+fn expect_padding(reader: &mut BitReader) -> Result<(), String> {
+    while reader.remaining() > 0 {
+        let offset = reader.position();
+        let count = if reader.remaining() < 64 { reader.remaining() } else { 64 };
+        let bits = reader.read_bits(count, "padding")?;
+        if bits != 0 {
+            return Err(format!(
+                "Non-zero trailing data at bit {}",
+                offset + count - 1 - (63 - bits.leading_zeros() as usize)
+            ));
+        }
+    }
+    return Ok(());
+}
+
+// How deep packets may nest.  Decoding keeps its own stack, but evaluating, printing and encoding
+// all recurse, and would run out of real stack long before this ran out of memory.
+pub const MAX_DEPTH: usize = 1000;
+
+// An operator whose sub-packets are still being read.
+struct Open {
+    offset: usize,
+    version: u8,
+    type_id: u8,
+    length_type: LengthType,
+    length: u64,
+    body_offset: usize,
+    trace: Option<usize>,
+    sub_packets: Vec<Packet>
+}
+
+impl Open {
+    fn is_complete(&self, reader: &BitReader) -> Result<bool, String> {
+        return match self.length_type {
+            LengthType::Count => Ok(self.sub_packets.len() as u64 == self.length),
+            LengthType::Bits => {
+                let end = self.body_offset + self.length as usize;
+                if reader.position() > end {
+                    return Err(format!(
+                        "Sub-packets overran their length, ending at bit {} instead of bit {}",
+                        reader.position(), end
+                    ));
+                }
+                Ok(reader.position() == end)
+            }
+        };
+    }
+
+    fn close(self, reader: &BitReader, traces: &mut Option<&mut Vec<Trace>>) -> Result<Packet, String> {
+        // Comparisons only make sense between two things.
+        if self.type_id >= 5 && self.sub_packets.len() != 2 {
+            return Err(format!(
+                "{} packet at bit {} needs exactly 2 sub-packets but has {}",
+                operator_name(self.type_id), self.offset, self.sub_packets.len()
+            ));
+        }
+        if let (Some(t), Some(i)) = (traces.as_mut(), self.trace) {
+            t[i].end = reader.position();
+        }
+        return Ok(Packet::Operator(self.version, self.type_id, self.sub_packets));
+    }
+}
+
+// Reads packets header by header, keeping the operators still waiting on sub-packets on a stack
+// of its own, so nesting is limited by MAX_DEPTH rather than by how much stack there is.
+fn read_packet(reader: &mut BitReader, mut traces: Option<&mut Vec<Trace>>) -> Result<Packet, String> {
+    let mut open: Vec<Open> = Vec::new();
+    loop {
+        let offset = reader.position();
+        if open.len() > MAX_DEPTH {
+            return Err(format!("Packet at bit {} is nested more than {} deep", offset, MAX_DEPTH));
+        }
+        let version = reader.read_bits(3, "version")? as u8;
+        let type_id = reader.read_bits(3, "type ID")? as u8;
+        let mut finished = if type_id == 4 {
+            let body_offset = reader.position();
+            let value = parse_literal(reader)?;
+            if let Some(t) = traces.as_mut() {
+                t.push(Trace {
+                    offset: offset,
+                    depth: open.len(),
+                    version: version,
+                    type_id: type_id,
+                    body: Body::Literal(value.clone()),
+                    body_offset: body_offset,
+                    end: reader.position()
+                });
+            }
+            Some(Packet::Value(version, type_id, value))
+        } else {
+            let length_type = if reader.read_bits(1, "length type ID")? == 0 { LengthType::Bits } else { LengthType::Count };
+            let length = match length_type {
+                LengthType::Bits => reader.read_bits(15, "sub-packet length")?,
+                LengthType::Count => reader.read_bits(11, "sub-packet count")?
+            };
+            let body_offset = reader.position();
+            if let LengthType::Bits = length_type {
+                if length as usize > reader.remaining() {
+                    return Err(format!(
+                        "Sub-packet length {} at bit {} exceeds the {} bits left",
+                        length, body_offset, reader.remaining()
+                    ));
+                }
+            }
+            // Sub-packets are traced after their parent, so reserve its slot now.
+            let trace = traces.as_mut().map(|t| {
+                t.push(Trace {
+                    offset: offset,
+                    depth: open.len(),
+                    version: version,
+                    type_id: type_id,
+                    body: Body::Operator(length_type, length),
+                    body_offset: body_offset,
+                    end: body_offset
+                });
+                return t.len() - 1;
+            });
+            open.push(Open {
+                offset: offset,
+                version: version,
+                type_id: type_id,
+                length_type: length_type,
+                length: length,
+                body_offset: body_offset,
+                trace: trace,
+                sub_packets: Vec::new()
+            });
+            None
+        };
+
+        // Hand the packet to its parent, closing every operator that now has all it needs.
+        loop {
+            if let Some(packet) = finished.take() {
+                match open.last_mut() {
+                    Some(parent) => parent.sub_packets.push(packet),
+                    None => return Ok(packet)
+                }
+            }
+            match open.last() {
+                Some(parent) if parent.is_complete(reader)? => (),
+                _ => break
+            }
+            if let Some(parent) = open.pop() {
+                finished = Some(parent.close(reader, &mut traces)?);
+            }
+        }
+    }
+}
+
+fn parse_literal(reader: &mut BitReader) -> Result<BigUint, String> {
+    let mut nibbles = Vec::new();
+    loop {
+        let group = reader.read_bits(5, "literal group")?;
+        nibbles.push((group & 0xF) as u8);
+        if group & 0x10 == 0 {
+            return Ok(BigUint::from_nibbles(&nibbles));
+        }
+    }
+}
+
+pub fn operator_name(type_id: u8) -> &'static str {
+    return match type_id {
+        0 => "sum",
+        1 => "product",
+        2 => "min",
+        3 => "max",
+        4 => "literal",
+        5 => "gt",
+        6 => "lt",
+        7 => "eq",
+        _ => "unknown"
+    };
+}
+
+// What packets can be evaluated in: checked u64, or BigUint which never overflows.
+pub trait Number: Ord + Clone + fmt::Display {
+    fn from_literal(literal: &BigUint) -> Option<Self>;
+    fn from_bool(b: bool) -> Self;
+    fn plus(&self, other: &Self) -> Option<Self>;
+    fn times(&self, other: &Self) -> Option<Self>;
+}
+
+impl Number for u64 {
+    fn from_literal(literal: &BigUint) -> Option<u64> {
+        return literal.to_u64();
+    }
+    fn from_bool(b: bool) -> u64 {
+        return if b { 1 } else { 0 };
+    }
+    fn plus(&self, other: &u64) -> Option<u64> {
+        return self.checked_add(*other);
+    }
+    fn times(&self, other: &u64) -> Option<u64> {
+        return self.checked_mul(*other);
+    }
+}
+
+impl Number for BigUint {
+    fn from_literal(literal: &BigUint) -> Option<BigUint> {
+        return Some(literal.clone());
+    }
+    fn from_bool(b: bool) -> BigUint {
+        return BigUint::from_u64(if b { 1 } else { 0 });
+    }
+    fn plus(&self, other: &BigUint) -> Option<BigUint> {
+        return Some(BigUint::plus(self, other));
+    }
+    fn times(&self, other: &BigUint) -> Option<BigUint> {
+        return Some(BigUint::times(self, other));
+    }
+}
+
+pub fn evaluate(p: &Packet) -> Result<u64, String> {
+    return evaluate_at(p, &mut Vec::new());
+}
+
+pub fn evaluate_big(p: &Packet) -> Result<BigUint, String> {
+    return evaluate_at(p, &mut Vec::new());
+}
+
+// `path` holds the sub-packet indices leading to `p`, to say where things went wrong.
+fn evaluate_at<N: Number>(p: &Packet, path: &mut Vec<usize>) -> Result<N, String> {
+    return match p {
+        Packet::Value(_, _, v) => N::from_literal(v)
+            .ok_or(format!("Literal {} at {} is too large", v, describe_path(path))),
+        Packet::Operator(_, t, sub_packets) => {
+            let mut sub_expressions = Vec::new();
+            for (i, sub_packet) in sub_packets.iter().enumerate() {
+                path.push(i);
+                sub_expressions.push(evaluate_at(sub_packet, path)?);
+                path.pop();
+            }
+            return apply(*t, sub_expressions)
+                .map_err(|msg| format!("{} at {}", msg, describe_path(path)));
+        }
+    }
+}
+
+fn describe_path(path: &Vec<usize>) -> String {
+    return iter::once("root".to_string())
+        .chain(path.iter().map(|i| i.to_string()))
+        .collect::<Vec<String>>()
+        .join(".");
+}
+
+fn apply<N: Number>(t: u8, sub_expressions: Vec<N>) -> Result<N, String> {
+    return match t {
+        0 => sub_expressions.iter()
+            .try_fold(N::from_bool(false), |acc, v| acc.plus(v))
+            .ok_or(String::from("Overflow in sum")),
+        1 => sub_expressions.iter()
+            .try_fold(N::from_bool(true), |acc, v| acc.times(v))
+            .ok_or(String::from("Overflow in product")),
+        2 => sub_expressions.into_iter().min().ok_or("No Subpackets on min".to_string()),
+        3 => sub_expressions.into_iter().max().ok_or("No Subpackets on max".to_string()),
+        5 => Ok(N::from_bool(sub_expressions.get(0).ok_or("No subpackets on GT")? > sub_expressions.get(1).ok_or("Only 1 subpacket on GT")?)),
+        6 => Ok(N::from_bool(sub_expressions.get(0).ok_or("No subpackets on LT")? < sub_expressions.get(1).ok_or("Only 1 subpacket on LT")?)),
+        7 => Ok(N::from_bool(sub_expressions.get(0).ok_or("No subpackets on Eq")? == sub_expressions.get(1).ok_or("Only 1 subpacket on EQ")?)),
+        _ => Err(String::from("Unrecognized type"))
+
+    }
+}
+
+// `(sum 1 (product 2 3))`
+pub fn to_expression(p: &Packet) -> String {
+    return match p {
+        Packet::Value(_, _, v) => format!("{}", v),
+        Packet::Operator(_, t, sub_packets) => format!(
+            "({} {})",
+            operator_name(*t),
+            sub_packets.iter().map(to_expression).collect::<Vec<String>>().join(" ")
+        )
+    };
+}
+
+// `(sum 1 (product 2 3)=6)=7`, or the first error hit while evaluating.
+pub fn to_annotated_expression(p: &Packet) -> Result<String, String> {
+    return annotate(p).map(|(expression, _)| expression);
+}
+
+fn annotate(p: &Packet) -> Result<(String, BigUint), String> {
+    return match p {
+        Packet::Value(_, _, v) => Ok((format!("{}", v), v.clone())),
+        Packet::Operator(_, t, sub_packets) => {
+            let (expressions, values): (Vec<String>, Vec<BigUint>) = sub_packets.iter()
+                .map(annotate)
+                .collect::<Result<Vec<(String, BigUint)>, String>>()?
+                .into_iter()
+                .unzip();
+            let value = apply(*t, values)?;
+            Ok((format!("({} {})={}", operator_name(*t), expressions.join(" "), value), value))
+        }
+    };
+}
+
+#[derive(Clone, Copy)]
+pub enum LengthType {
+    Bits,
+    Count
+}
+
+pub fn encode(packet: &Packet, length_type: fn(&Packet) -> LengthType) -> Result<String, String> {
+    let mut writer = BitWriter::new();
+    write_packet(&mut writer, packet, length_type)?;
+    return Ok(writer.to_hex());
+}
+
+pub fn write_packet(writer: &mut BitWriter, packet: &Packet, length_type: fn(&Packet) -> LengthType) -> Result<(), String> {
+    let (version, type_id) = match packet {
+        Packet::Value(v, t, _) | Packet::Operator(v, t, _) => (*v, *t)
+    };
+    if version > 7 || type_id > 7 {
+        return Err(format!("Version {} or type {} does not fit in 3 bits", version, type_id));
+    }
+    writer.write_bits(version as u64, 3);
+    writer.write_bits(type_id as u64, 3);
+    return match packet {
+        Packet::Value(_, 4, value) => {
+            write_literal(writer, value);
+            Ok(())
+        },
+        Packet::Value(_, t, _) => Err(format!("Literal packets must have type 4, not {}", t)),
+        Packet::Operator(_, 4, _) => Err(String::from("Operator packets cannot have type 4")),
+        Packet::Operator(_, _, sub_packets) => match length_type(packet) {
+            LengthType::Bits => write_packets_by_length(writer, sub_packets, length_type),
+            LengthType::Count => write_packets_by_count(writer, sub_packets, length_type)
+        }
+    };
+}
+
+fn write_literal(writer: &mut BitWriter, value: &BigUint) {
+    let nibbles = value.nibbles();
+    for (i, nibble) in nibbles.iter().enumerate() {
+        writer.write_bits(if i + 1 == nibbles.len() { 0 } else { 1 }, 1);
+        writer.write_bits(*nibble as u64, 4);
+    }
+}
+
+fn write_packets_by_count(writer: &mut BitWriter, sub_packets: &Vec<Packet>, length_type: fn(&Packet) -> LengthType) -> Result<(), String> {
+    if sub_packets.len() >= 1 << 11 {
+        return Err(format!("{} sub-packets do not fit in an 11 bit count", sub_packets.len()));
+    }
+    writer.write_bits(1, 1);
+    writer.write_bits(sub_packets.len() as u64, 11);
+    return sub_packets.iter().map(|p| write_packet(writer, p, length_type)).collect();
+}
+
+fn write_packets_by_length(writer: &mut BitWriter, sub_packets: &Vec<Packet>, length_type: fn(&Packet) -> LengthType) -> Result<(), String> {
+    writer.write_bits(0, 1);
+    let length_at = writer.position();
+    writer.write_bits(0, 15);
+    for p in sub_packets {
+        write_packet(writer, p, length_type)?;
+    }
+    let length = writer.position() - length_at - 15;
+    if length >= 1 << 15 {
+        return Err(format!("{} bits of sub-packets do not fit in a 15 bit length", length));
+    }
+    writer.patch_bits(length_at, length as u64, 15);
+    return Ok(());
+}
+
+enum Token {
+    Open,
+    Close,
+    Word(String)
+}
+
+// Compiles `(sum 1 (product 2 3))` into the packets that would evaluate it.
+pub fn compile(source: &str) -> Result<Packet, String> {
+    let tokens = tokenize(source);
+    let mut position = 0;
+    let packet = compile_expression(&tokens, &mut position)?;
+    if let Some((column, _)) = tokens.get(position) {
+        return Err(format!("Unexpected input after expression at column {}", column));
+    }
+    return Ok(packet);
+}
+
+fn tokenize(source: &str) -> Vec<(usize, Token)> {
+    let mut tokens = Vec::new();
+    let mut word: Option<(usize, String)> = None;
+    for (column, c) in source.chars().enumerate() {
+        if c == '(' || c == ')' || c.is_whitespace() {
+            if let Some((start, w)) = word.take() {
+                tokens.push((start, Token::Word(w)));
+            }
+        }
+        match c {
+            '(' => tokens.push((column, Token::Open)),
+            ')' => tokens.push((column, Token::Close)),
+            _ if c.is_whitespace() => (),
+            _ => match word.as_mut() {
+                Some((_, w)) => w.push(c),
+                None => word = Some((column, c.to_string()))
+            }
+        }
+    }
+    if let Some((start, w)) = word {
+        tokens.push((start, Token::Word(w)));
+    }
+    return tokens;
+}
+
+fn compile_expression(tokens: &Vec<(usize, Token)>, position: &mut usize) -> Result<Packet, String> {
+    let (column, token) = tokens.get(*position)
+        .ok_or(String::from("Unexpected end of expression"))?;
+    *position += 1;
+    return match token {
+        Token::Word(w) => BigUint::parse_decimal(w)
+            .map(|v| Packet::Value(0, 4, v))
+            .ok_or(format!("Bad literal '{}' at column {}", w, column)),
+        Token::Close => Err(format!("Unexpected ')' at column {}", column)),
+        Token::Open => {
+            let type_id = match tokens.get(*position) {
+                Some((word_column, Token::Word(w))) => operator_type(w)
+                    .ok_or(format!("Unknown operator '{}' at column {}", w, word_column))?,
+                _ => return Err(format!("Expected an operator after '(' at column {}", column))
+            };
+            *position += 1;
+            let mut operands = Vec::new();
+            loop {
+                match tokens.get(*position) {
+                    Some((_, Token::Close)) => break,
+                    Some(_) => operands.push(compile_expression(tokens, position)?),
+                    None => return Err(format!("Unclosed '(' at column {}", column))
+                }
+            }
+            *position += 1;
+            match (type_id, operands.len()) {
+                (5..=7, 2) => (),
+                (5..=7, n) => return Err(format!(
+                    "{} at column {} takes exactly 2 operands, not {}", operator_name(type_id), column, n
+                )),
+                (_, 0) => return Err(format!(
+                    "{} at column {} needs at least one operand", operator_name(type_id), column
+                )),
+                _ => ()
+            }
+            Ok(Packet::Operator(0, type_id, operands))
+        }
+    };
+}
+
+fn operator_type(name: &str) -> Option<u8> {
+    return (0..8).filter(|t| *t != 4).find(|t| operator_name(*t) == name);
+}
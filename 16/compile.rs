@@ -0,0 +1,138 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+
+mod bits;
+use bits::{BitReader, LengthType, Packet, compile, encode, evaluate, evaluate_big, parse_packet};
+
+const EXAMPLES: [(&str, u64); 8] = [
+    ("(sum 1 2)", 3),
+    ("(product 6 9)", 54),
+    ("(min 7 8 9)", 7),
+    ("(max 7 8 9)", 9),
+    ("(lt 5 15)", 1),
+    ("(gt 5 15)", 0),
+    ("(eq 5 15)", 0),
+    ("(eq (sum 1 3) (product 2 2))", 1)
+];
+
+// Evaluated with big integers, and the error from checked u64 evaluation.
+const OVERFLOWING: [(&str, &str, &str); 5] = [
+    (
+        "(product 18446744073709551615 2)",
+        "36893488147419103230",
+        "Overflow in product at root"
+    ),
+    (
+        "(sum 340282366920938463463374607431768211455 1)",
+        "340282366920938463463374607431768211456",
+        "Literal 340282366920938463463374607431768211455 at root.0 is too large"
+    ),
+    (
+        "(gt (product 4294967296 4294967296) 18446744073709551615)",
+        "1",
+        "Overflow in product at root.0"
+    ),
+    (
+        "(eq (product 1000000000 1000000000000) (product 1000000 1000000000000000))",
+        "1",
+        "Overflow in product at root.0"
+    ),
+    (
+        "(sum 1 (max 2 (product 4294967296 4294967296)))",
+        "18446744073709551617",
+        "Overflow in product at root.1.1"
+    )
+];
+
+const REJECTED: [(&str, &str); 9] = [
+    ("(gt 1)", "gt at column 0 takes exactly 2 operands, not 1"),
+    ("(eq 1 2 3)", "eq at column 0 takes exactly 2 operands, not 3"),
+    ("(sum)", "sum at column 0 needs at least one operand"),
+    ("(sum 1 2", "Unclosed '(' at column 0"),
+    ("(nand 1 2)", "Unknown operator 'nand' at column 1"),
+    ("(  nand 1 2)", "Unknown operator 'nand' at column 3"),
+    ("(sum 1 (\tfoo 1 2))", "Unknown operator 'foo' at column 9"),
+    ("(sum 1 -2)", "Bad literal '-2' at column 7"),
+    ("(sum 1 2) 3", "Unexpected input after expression at column 10")
+];
+
+const LENGTH_TYPE: fn(&Packet) -> LengthType = |_| LengthType::Bits;
+
+// Usage: compile [expression files...], one expression per line, defaulting to stdin.
+fn main() {
+    let results = EXAMPLES.iter()
+        .map(|(source, expected)| (
+            *source,
+            operation(source)
+                .and_then(|hex| BitReader::from_hex(&hex))
+                .and_then(|mut reader| parse_packet(&mut reader))
+                .and_then(|packet| evaluate(&packet))
+                .and_then(|actual| if *expected == actual {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {} but got {}", expected, actual));
+                })
+        ))
+        .chain(OVERFLOWING.iter().map(|(source, expected, overflow)| (
+            *source,
+            operation(source)
+                .and_then(|hex| BitReader::from_hex(&hex))
+                .and_then(|mut reader| parse_packet(&mut reader))
+                .and_then(|packet| match evaluate(&packet) {
+                    Ok(v) => Err(format!("Expected '{}' but evaluated to {}", overflow, v)),
+                    Err(msg) => if msg != *overflow {
+                        Err(format!("Expected '{}' but got '{}'", overflow, msg))
+                    } else {
+                        evaluate_big(&packet)
+                    }
+                })
+                .and_then(|actual| if *expected == actual.to_string() {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {} but got {}", expected, actual));
+                })
+        )))
+        .chain(REJECTED.iter().map(|(source, expected)| (
+            *source,
+            match operation(source) {
+                Ok(hex) => Err(format!("Expected an error but compiled to {}", hex)),
+                Err(msg) => if msg == *expected {
+                    Ok(())
+                } else {
+                    Err(format!("Expected '{}' but got '{}'", expected, msg))
+                }
+            }
+        )))
+        .collect::<Vec<(&str, Result<(), String>)>>();
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("Example {} passed.", name),
+            Err(msg) => println!("Example {} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("Please address errors before compiling anything.")
+    }
+
+    let files = env::args().skip(1).collect::<Vec<String>>();
+    let sources = if files.len() == 0 {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents).expect("Could not read stdin.");
+        vec![contents]
+    } else {
+        files.iter()
+            .map(|f| fs::read_to_string(f).expect("Could not read expression file."))
+            .collect()
+    };
+    for line in sources.iter().flat_map(|s| s.lines()).filter(|l| l.trim().len() > 0) {
+        match operation(line) {
+            Ok(hex) => println!("{}", hex),
+            Err(msg) => println!("Failed to compile {}: {}.", line, msg)
+        }
+    }
+}
+
+fn operation(source: &str) -> Result<String, String> {
+    return compile(source).and_then(|packet| encode(&packet, LENGTH_TYPE));
+}
@@ -0,0 +1,57 @@
+use std::env;
+use std::fs;
+
+mod bits;
+use bits::{BitReader, Body, LengthType, Trace, operator_name, to_annotated_expression, trace_packet};
+
+const DAY: u8 = 16;
+
+// Usage: disassemble [transmission files...], defaulting to the puzzle input.
+fn main() {
+    let mut files = env::args().skip(1).collect::<Vec<String>>();
+    if files.len() == 0 {
+        files.push(format!("{}/input", DAY));
+    }
+    for filename in files {
+        println!("{}:", filename);
+        match operation(filename.clone()) {
+            Ok(listing) => println!("{}", listing),
+            Err(msg) => println!("Failed to disassemble {}: {}.", filename, msg)
+        }
+    }
+}
+
+fn operation(filename: String) -> Result<String, String> {
+    let contents = fs::read_to_string(filename)
+        .map_err(|io_error| format!("{}", io_error))?;
+    let mut reader = BitReader::from_hex(contents.trim())?;
+    let (packet, traces) = trace_packet(&mut reader)?;
+    let listing = traces.iter()
+        .map(disassemble)
+        .collect::<Vec<String>>()
+        .join("\n");
+    let expression = to_annotated_expression(&packet)
+        .unwrap_or_else(|msg| format!("Evaluation failed: {}", msg));
+    return Ok(format!("{}\n{}\n", listing, expression));
+}
+
+fn disassemble(trace: &Trace) -> String {
+    let description = match &trace.body {
+        Body::Literal(value) => format!("value {}", value),
+        Body::Operator(LengthType::Bits, length) => format!(
+            "length type 0 ({} bits), sub-packets {}..{}", length, trace.body_offset, trace.end
+        ),
+        Body::Operator(LengthType::Count, count) => format!(
+            "length type 1 ({} packets), sub-packets {}..{}", count, trace.body_offset, trace.end
+        )
+    };
+    return format!(
+        "{:>8} {}v{} {}({}) {}",
+        trace.offset,
+        "  ".repeat(trace.depth),
+        trace.version,
+        operator_name(trace.type_id),
+        trace.type_id,
+        description
+    );
+}
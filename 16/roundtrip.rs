@@ -0,0 +1,144 @@
+use std::fs;
+
+#[path = "../shared/random.rs"]
+mod random;
+use random::Random;
+mod bits;
+use bits::{BigUint, BitReader, LengthType, MAX_DEPTH, Packet, encode, parse_packet};
+
+const EXAMPLES: [&str; 15] = [
+    "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15"
+];
+
+const GENERATED: u64 = 500;
+
+const MALFORMED: [(&str, &str); 8] = [
+    ("", "Ran out of input reading version at bit 0: needed 3 bits, only 0 left"),
+    ("D2FE2", "Ran out of input reading literal group at bit 16: needed 5 bits, only 4 left"),
+    ("D2FE29", "Non-zero trailing data at bit 23"),
+    ("D2FE2G", "Unrecognized Hex Digit 'G' at bit 20"),
+    ("38006F", "Sub-packet length 27 at bit 22 exceeds the 2 bits left"),
+    ("3800", "Ran out of input reading sub-packet length at bit 7: needed 15 bits, only 9 left"),
+    ("1600C40881102", "gt packet at bit 0 needs exactly 2 sub-packets but has 3"),
+    ("1E004408", "eq packet at bit 0 needs exactly 2 sub-packets but has 1")
+];
+
+const DAY: u8 = 16;
+
+const LENGTH_TYPES: [(&str, fn(&Packet) -> LengthType); 3] = [
+    ("bits", |_| LengthType::Bits),
+    ("count", |_| LengthType::Count),
+    ("mixed", |p| match p {
+        Packet::Operator(_, _, sub_packets) if sub_packets.len() % 2 == 0 => LengthType::Bits,
+        _ => LengthType::Count
+    })
+];
+
+fn main() {
+    let mut results = EXAMPLES.iter()
+        .map(|name| (
+            format!("Example {}", name),
+            fs::read_to_string(format!("{}/{}.ie", DAY, name))
+                .map_err(|io_error| format!("{}", io_error))
+                .and_then(parse)
+                .and_then(|p| round_trip(&p))
+        ))
+        .collect::<Vec<(String, Result<(), String>)>>();
+    results.extend(
+        (1..=GENERATED).map(|seed| (
+            format!("Generated tree {}", seed),
+            round_trip(&generate(&mut Random::new(seed), 0))
+        ))
+    );
+    results.extend(
+        MALFORMED.iter().map(|(hex, expected)| (
+            format!("Malformed '{}'", hex),
+            match parse(hex.to_string()) {
+                Ok(p) => Err(format!("Expected an error but parsed {:?}", p)),
+                Err(actual) => if *expected == actual {
+                    Ok(())
+                } else {
+                    Err(format!("Expected '{}' but got '{}'", expected, actual))
+                }
+            }
+        ))
+    );
+    results.push((
+        format!("Nested {} deep", MAX_DEPTH),
+        round_trip(&nest(MAX_DEPTH))
+    ));
+    // Every operator header is 18 bits with a count, so the innermost packet starts 18 bits per
+    // level in.
+    results.push((
+        format!("Nested {} deep", MAX_DEPTH + 1),
+        encode(&nest(MAX_DEPTH + 1), |_| LengthType::Count)
+            .and_then(|hex| match parse(hex) {
+                Ok(_) => Err(String::from("Expected an error but parsed it")),
+                Err(actual) => {
+                    let expected = format!(
+                        "Packet at bit {} is nested more than {} deep", 18 * (MAX_DEPTH + 1), MAX_DEPTH
+                    );
+                    if expected == actual {
+                        Ok(())
+                    } else {
+                        Err(format!("Expected '{}' but got '{}'", expected, actual))
+                    }
+                }
+            })
+    ));
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("{} passed.", name),
+            Err(msg) => println!("{} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("The encoder or parser is broken.")
+    }
+}
+
+fn parse(contents: String) -> Result<Packet, String> {
+    let mut reader = BitReader::from_hex(contents.trim())?;
+    return parse_packet(&mut reader);
+}
+
+fn round_trip(packet: &Packet) -> Result<(), String> {
+    for (name, length_type) in LENGTH_TYPES.iter() {
+        let hex = encode(packet, *length_type)?;
+        let parsed = parse(hex.clone())?;
+        if &parsed != packet {
+            return Err(format!("{} encoding {} parsed as {:?}, expected {:?}", name, hex, parsed, packet));
+        }
+    }
+    return Ok(());
+}
+
+fn generate(random: &mut Random, depth: u64) -> Packet {
+    let version = random.below(8) as u8;
+    if random.below(6) < depth {
+        // Up to 100 bits, so some literals need more than a u64.
+        let nibbles = (0..=random.below(25))
+            .map(|_| random.below(16) as u8)
+            .collect::<Vec<u8>>();
+        return Packet::Value(version, 4, BigUint::from_nibbles(&nibbles));
+    }
+    let type_id = [0, 1, 2, 3, 5, 6, 7][random.below(7) as usize];
+    let count = match type_id {
+        5 | 6 | 7 => 2,
+        _ => 1 + random.below(5)
+    };
+    return Packet::Operator(
+        version,
+        type_id,
+        (0..count).map(|_| generate(random, depth + 1)).collect()
+    );
+}
+
+// A literal wrapped in `depth` sums of one.
+fn nest(depth: usize) -> Packet {
+    let mut packet = Packet::Value(0, 4, BigUint::from_u64(7));
+    for _ in 0..depth {
+        packet = Packet::Operator(1, 0, vec![packet]);
+    }
+    return packet;
+}
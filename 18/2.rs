@@ -1,13 +1,17 @@
 use std::fs;
-use std::str::FromStr;
 use std::fmt;
-use std::ops::Add;
+use std::str::FromStr;
+use std::thread;
+
+mod snailfish;
+use snailfish::SnailfishNumber;
 
 type ParseTarget = Vec<SnailfishNumber>;
-type Solution = u64;
+type Solution = LargestSum;
 
-const EXAMPLES: [(&str, Solution); 1] = [
-    ("1", 3993)
+const EXAMPLES: [(&str, Solution); 2] = [
+    ("1", LargestSum { magnitude: 3993, lhs: 9, rhs: 1 }),
+    ("2", LargestSum { magnitude: 225, lhs: 1, rhs: 2 })
 ];
 
 const DAY: u8 = 18;
@@ -47,9 +51,9 @@ fn main() {
     );
 }
 
-fn error<T>(msg: &str) -> Result<T, String> {
-    return Err(String::from(msg));
-}
+//fn error<T>(msg: &str) -> Result<T, String> {
+//    return Err(String::from(msg));
+//}
 
 fn operation(filename: String) -> Result<Solution, String> {
     return fs::read_to_string(filename)
@@ -58,156 +62,58 @@ fn operation(filename: String) -> Result<Solution, String> {
         .and_then(solve);
 }
 
-#[derive(Clone, PartialEq, Eq)]
-enum SnailfishNumber {
-    Pair(Box<SnailfishNumber>, Box<SnailfishNumber>),
-    Single(u8)
-}
-
-impl FromStr for SnailfishNumber {
-    type Err = String;
-    fn from_str(s: &str) -> Result<SnailfishNumber, String> {
-        let mut stack = Vec::new();
-        for c in s.chars() {
-            if c.is_digit(10) {
-                stack.push(SnailfishNumber::Single(
-                    c.to_digit(10)
-                        .unwrap() as u8
-                ))
-            } else if c == ']' {
-                // Backwards because STACKS
-                let rhs = stack.pop().ok_or("Malformed Number".to_string())?;
-                let lhs = stack.pop().ok_or("Malformed Number".to_string())?;
-                
-                stack.push(lhs + rhs);
-            }
-        }
-        if stack.len() == 1 {
-            return Ok(stack.pop().unwrap());
-        }
-        return error("unfinished number");
-    }
-}
-
-
-impl fmt::Display for SnailfishNumber {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        return match self {
-            SnailfishNumber::Pair(lhs, rhs) => write!(f, "[{}, {}]", lhs, rhs),
-            SnailfishNumber::Single(val) => write!(f, "{}", val)
-        }
-    }
-}
-
 fn parse(contents: String) -> Result<ParseTarget, String> {
     return contents.lines()
         .map(FromStr::from_str).collect();
 
 }
 
-impl Add for SnailfishNumber {
-    type Output = SnailfishNumber;
-    fn add(self, rhs: SnailfishNumber) -> Self::Output {
-        return SnailfishNumber::Pair(
-            Box::new(self),
-            Box::new(rhs)
-        );
-    }
+// Line numbers are 1-based, as an editor would show them.
+#[derive(PartialEq, Clone, Copy)]
+struct LargestSum {
+    magnitude: u64,
+    lhs: usize,
+    rhs: usize
 }
 
-enum ExplodeResult {
-    Nothing(), //Nothing exploded
-    Done(), //SOmething exploded, but we're done.
-    Left(u8), //Explosion, remaining Leftward addition
-    Right(u8), //Explosion, remaining Rightward addition
-    Explosion(u8, u8) //Explosion
+impl fmt::Display for LargestSum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{} (line {} + line {})", self.magnitude, self.lhs, self.rhs);
+    }
 }
 
-impl SnailfishNumber {
-    fn explode(self, depth: u8) -> (ExplodeResult, SnailfishNumber) {
-        return match self {
-            SnailfishNumber::Single(n) => (ExplodeResult::Nothing(), SnailfishNumber::Single(n)),
-            SnailfishNumber::Pair(lhs, rhs) => match lhs.explode(depth + 1) {
-                (ExplodeResult::Done(), sn) => (ExplodeResult::Done(), sn + *rhs),
-                (ExplodeResult::Left(n), sn) => (ExplodeResult::Left(n), sn + *rhs),
-                (ExplodeResult::Right(n), sn) => (ExplodeResult::Done(), sn + rhs.add_left(n)),
-                (ExplodeResult::Explosion(l, r), sn) => (ExplodeResult::Left(l), sn + rhs.add_left(r)),
-                (ExplodeResult::Nothing(), lhs) => match rhs.explode(depth + 1) {
-                    (ExplodeResult::Done(), sn) => (ExplodeResult::Done(), lhs + sn),
-                    (ExplodeResult::Left(n), sn) => (ExplodeResult::Done(), lhs.add_right(n) + sn),
-                    (ExplodeResult::Right(n), sn) => (ExplodeResult::Right(n), lhs + sn),
-                    (ExplodeResult::Explosion(l, r), sn) => (ExplodeResult::Right(r), lhs.add_right(l) + sn),
-                    (ExplodeResult::Nothing(), rhs) => if depth >= 4 {
-                        (
-                            ExplodeResult::Explosion(lhs.magnitude() as u8, rhs.magnitude() as u8),
-                            SnailfishNumber::Single(0)
-                        )
-                    } else {
-                        (ExplodeResult::Nothing(), lhs + rhs)
-                    }
-                }
-            }
-        }
-    }
-    fn split(self) -> (bool, SnailfishNumber) {
-        return match self {
-            SnailfishNumber::Single(d) => if d >=10 {
-                (true, SnailfishNumber::Single(d/2) + SnailfishNumber::Single(d/2 + (d%2)))
-            } else {
-                (false, SnailfishNumber::Single(d))
-            },
-            SnailfishNumber::Pair(lhs, rhs) => match lhs.split() {
-                (true, sn) => (true, sn + *rhs),
-                (false, lhs) => match rhs.split() {
-                    (b, rhs) => (b, lhs + rhs)
-                }
-            }
-        }
-    }
-    fn reduce(self) -> SnailfishNumber {
-        let (result, exploded) = self.explode(0);
-        match result {
-            ExplodeResult::Nothing() => {
-                let (did_work, splitted) = exploded.split();
-                if !did_work {
-                    return splitted;
-                }
-                return splitted.reduce();
-            },
-            _ => { return exploded.reduce(); }
-        }
-    }
-    fn add_right(self, n: u8) -> SnailfishNumber {
-        return match self {
-            SnailfishNumber::Single(d) => SnailfishNumber::Single(d + n),
-            SnailfishNumber::Pair(lhs, rhs) => *lhs + rhs.add_right(n)
-        }
-    }
-    fn add_left(self, n: u8) -> SnailfishNumber {
-        return match self {
-            SnailfishNumber::Single(d) => SnailfishNumber::Single(d + n),
-            SnailfishNumber::Pair(lhs, rhs) => lhs.add_left(n) + *rhs
-        }
-    }
-    fn magnitude(&self) -> u64 {
-        return match self {
-            SnailfishNumber::Single(d) => *d as u64,
-            SnailfishNumber::Pair(l, r) => 3 * l.magnitude() + 2 * r.magnitude()
-        }
+impl LargestSum {
+    // Ties go to the earliest pair of lines, so the answer doesn't depend on thread timing.
+    fn better(self, other: LargestSum) -> LargestSum {
+        return if (other.magnitude, self.lhs, self.rhs) > (self.magnitude, other.lhs, other.rhs) {
+            other
+        } else {
+            self
+        };
     }
 }
 
 fn solve(parsed: ParseTarget) -> Result<Solution, String> {
-    return pairs(parsed).into_iter()
-        .map(|(l, r)| (l + r).reduce().magnitude())
-        .max()
-        .ok_or("Maximum of an empty list is impossible.".to_string());
+    let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let numbers = &parsed;
+    return thread::scope(|scope| {
+        let handles = (0..workers)
+            .map(|worker| scope.spawn(move || largest_sum(numbers, worker, workers)))
+            .collect::<Vec<_>>();
+        return handles.into_iter()
+            .flat_map(|h| h.join().expect("Worker thread panicked."))
+            .reduce(LargestSum::better);
+    }).ok_or("Maximum of an empty list is impossible.".to_string());
 }
 
-fn pairs<T: PartialEq<T> + Clone>(list: Vec<T>) -> Vec<(T,T)> {
-    return list.iter()
-        .flat_map(|l| list.iter()
-            .map(move |r| (l.clone(), r.clone()))
-        ).filter(|(l, r)| l != r)
-        .collect();
+// Every ordered pair of distinct lines whose left hand side belongs to this worker.
+fn largest_sum(numbers: &Vec<SnailfishNumber>, worker: usize, workers: usize) -> Option<LargestSum> {
+    return (worker..numbers.len()).step_by(workers)
+        .flat_map(|l| (0..numbers.len()).filter(move |r| *r != l).map(move |r| (l, r)))
+        .map(|(l, r)| LargestSum {
+            magnitude: (numbers[l].clone() + numbers[r].clone()).magnitude(),
+            lhs: l + 1,
+            rhs: r + 1
+        })
+        .reduce(LargestSum::better);
 }
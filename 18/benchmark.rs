@@ -0,0 +1,157 @@
+use std::env;
+use std::fs;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[path = "../shared/random.rs"]
+mod random;
+use random::Random;
+mod flat;
+mod snailfish;
+use flat::FlatSnailfishNumber;
+use snailfish::{PUZZLE_RULES, Rules, SnailfishNumber};
+
+const DAY: u8 = 18;
+
+const GENERATED: u64 = 5;
+
+const GENERATED_SIZE: usize = 100;
+
+// Homework is nested at most four deep, but nothing stops a number being nested deeper, and then
+// explosions can leave more explosions behind them.
+const DEEP: [&str; 2] = [
+    "[[[[[[1,2],3],4],5],6],7]\n[1,1]",
+    "[[[[[9,8],1],2],3],4]\n[1,1]"
+];
+
+const DEEP_GENERATED: u64 = 5;
+
+const DEEP_GENERATED_SIZE: usize = 30;
+
+const DEEP_GENERATED_DEPTH: u64 = 8;
+
+// Other rules the two should agree under too, tried on the deep homework and one generated deep.
+const OTHER_RULES: [Rules; 4] = [
+    Rules { explode_depth: 3, split_threshold: 10 },
+    Rules { explode_depth: 5, split_threshold: 10 },
+    Rules { explode_depth: 4, split_threshold: 7 },
+    Rules { explode_depth: 1, split_threshold: 2 }
+];
+
+// Usage: benchmark [homework files...], defaulting to the example and puzzle input.
+fn main() {
+    let mut files = env::args().skip(1).collect::<Vec<String>>();
+    if files.len() == 0 {
+        files.push(format!("{}/1.ie", DAY));
+        files.push(format!("{}/input", DAY));
+    }
+    let mut inputs = files.into_iter()
+        .flat_map(|f| fs::read_to_string(&f).ok().map(|contents| (f, contents, PUZZLE_RULES)))
+        .collect::<Vec<(String, String, Rules)>>();
+    inputs.extend(DEEP.iter().map(|contents| (format!("{:?}", contents), contents.to_string(), PUZZLE_RULES)));
+    inputs.extend((1..=GENERATED).map(|seed| (
+        format!("generated {}", seed),
+        generate_homework(&mut Random::new(seed), GENERATED_SIZE, 4),
+        PUZZLE_RULES
+    )));
+    inputs.extend((1..=DEEP_GENERATED).map(|seed| (
+        format!("generated {} deep", seed),
+        generate_homework(&mut Random::new(seed), DEEP_GENERATED_SIZE, DEEP_GENERATED_DEPTH),
+        PUZZLE_RULES
+    )));
+    for rules in OTHER_RULES.iter() {
+        let under = format!("exploding at {} and splitting at {}", rules.explode_depth, rules.split_threshold);
+        inputs.extend(DEEP.iter().map(|contents| (
+            format!("{:?} {}", contents, under),
+            contents.to_string(),
+            *rules
+        )));
+        inputs.push((
+            format!("generated 1 deep {}", under),
+            generate_homework(&mut Random::new(1), DEEP_GENERATED_SIZE, DEEP_GENERATED_DEPTH),
+            *rules
+        ));
+    }
+
+    let results = inputs.iter()
+        .map(|(name, contents, rules)| (name, compare(contents, rules)))
+        .collect::<Vec<(&String, Result<String, String>)>>();
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(report) => println!("{}: {}", name, report),
+            Err(msg) => println!("{} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("The tree and flat representations disagree.")
+    }
+}
+
+fn compare(contents: &str, rules: &Rules) -> Result<String, String> {
+    let trees = contents.lines()
+        .map(SnailfishNumber::from_str)
+        .collect::<Result<Vec<SnailfishNumber>, String>>()?;
+    let flats = contents.lines()
+        .map(FlatSnailfishNumber::from_str)
+        .collect::<Result<Vec<FlatSnailfishNumber>, String>>()?;
+
+    let tree_add = |l: SnailfishNumber, r| l.add_with(r, rules);
+    let flat_add = |l: FlatSnailfishNumber, r| (l + r).reduce_with(rules);
+    let (tree_sum, tree_sum_time) = timed(|| sum_magnitude(&trees, tree_add, SnailfishNumber::magnitude));
+    let (flat_sum, flat_sum_time) = timed(|| sum_magnitude(&flats, flat_add, FlatSnailfishNumber::magnitude));
+    let (tree_max, tree_max_time) = timed(|| max_magnitude(&trees, tree_add, SnailfishNumber::magnitude));
+    let (flat_max, flat_max_time) = timed(|| max_magnitude(&flats, flat_add, FlatSnailfishNumber::magnitude));
+
+    let empty = || String::from("No numbers to add");
+    let (tree_sum, flat_sum) = (tree_sum.ok_or_else(empty)?, flat_sum.ok_or_else(empty)?);
+    let (tree_max, flat_max) = (tree_max.ok_or_else(empty)?, flat_max.ok_or_else(empty)?);
+
+    if tree_sum != flat_sum {
+        return Err(format!("Sum magnitude was {} as a tree but {} flat", tree_sum, flat_sum));
+    }
+    if tree_max != flat_max {
+        return Err(format!("Largest pair magnitude was {} as a tree but {} flat", tree_max, flat_max));
+    }
+    return Ok(format!(
+        "sum {} (tree {:?}, flat {:?}), largest pair {} (tree {:?}, flat {:?})",
+        tree_sum, tree_sum_time, flat_sum_time, tree_max, tree_max_time, flat_max_time
+    ));
+}
+
+fn timed<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    return (result, start.elapsed());
+}
+
+fn sum_magnitude<T: Clone, A: Fn(T, T) -> T>(numbers: &Vec<T>, add: A, magnitude: fn(&T) -> u64) -> Option<u64> {
+    let mut iter = numbers.iter().cloned();
+    let first = iter.next()?;
+    return Some(magnitude(&iter.fold(first, |l, r| add(l, r))));
+}
+
+fn max_magnitude<T: Clone, A: Fn(T, T) -> T>(numbers: &Vec<T>, add: A, magnitude: fn(&T) -> u64) -> Option<u64> {
+    return (0..numbers.len())
+        .flat_map(|l| (0..numbers.len()).filter(move |r| *r != l).map(move |r| (l, r)))
+        .map(|(l, r)| magnitude(&add(numbers[l].clone(), numbers[r].clone())))
+        .max();
+}
+
+fn generate_homework(random: &mut Random, size: usize, max_depth: u64) -> String {
+    return (0..size)
+        .map(|_| generate_number(random, 1, max_depth))
+        .collect::<Vec<String>>()
+        .join("\n");
+}
+
+// Single digits, nested at most `max_depth` deep - four for homework that's already reduced.
+fn generate_number(random: &mut Random, depth: u64, max_depth: u64) -> String {
+    let element = |random: &mut Random| if depth < max_depth && random.below(3) > 0 {
+        generate_number(random, depth + 1, max_depth)
+    } else {
+        random.below(10).to_string()
+    };
+    let lhs = element(random);
+    let rhs = element(random);
+    return format!("[{},{}]", lhs, rhs);
+}
@@ -0,0 +1,109 @@
+// Shared by every day 18 program, none of which uses all of it.
+#![allow(dead_code)]
+
+use std::str::FromStr;
+use std::ops::Add;
+
+use snailfish::{PUZZLE_RULES, Rules, SnailfishNumber};
+
+// The regular numbers of a snailfish number, left to right, each with the number of pairs
+// enclosing it. `[[1,2],3]` is `[(1, 2), (2, 2), (3, 1)]`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct FlatSnailfishNumber {
+    elements: Vec<(u64, u8)>
+}
+
+impl FromStr for FlatSnailfishNumber {
+    type Err = String;
+    fn from_str(s: &str) -> Result<FlatSnailfishNumber, String> {
+        let mut elements = Vec::new();
+        flatten(&SnailfishNumber::from_str(s)?, 0, &mut elements);
+        return Ok(FlatSnailfishNumber { elements: elements });
+    }
+}
+
+fn flatten(number: &SnailfishNumber, depth: u8, elements: &mut Vec<(u64, u8)>) {
+    match number {
+        SnailfishNumber::Single(d) => elements.push((*d, depth)),
+        SnailfishNumber::Pair(lhs, rhs) => {
+            flatten(lhs, depth + 1, elements);
+            flatten(rhs, depth + 1, elements);
+        }
+    }
+}
+
+impl Add for FlatSnailfishNumber {
+    type Output = FlatSnailfishNumber;
+    fn add(self, rhs: FlatSnailfishNumber) -> Self::Output {
+        return FlatSnailfishNumber {
+            elements: self.elements.into_iter()
+                .chain(rhs.elements.into_iter())
+                .map(|(value, depth)| (value, depth + 1))
+                .collect()
+        };
+    }
+}
+
+impl FlatSnailfishNumber {
+    // Explodes the pair whose left element is at `i`.
+    fn explode_at(&mut self, i: usize) {
+        let (left, depth) = self.elements[i];
+        let (right, _) = self.elements.remove(i + 1);
+        if i > 0 {
+            self.elements[i - 1].0 += left;
+        }
+        if let Some(next) = self.elements.get_mut(i + 1) {
+            next.0 += right;
+        }
+        self.elements[i] = (0, depth - 1);
+    }
+
+    // Splits the element at `i`, returning whether the new pair is deep enough to explode.
+    fn split_at(&mut self, i: usize, rules: &Rules) -> bool {
+        let (value, depth) = self.elements[i];
+        self.elements[i] = (value / 2, depth + 1);
+        self.elements.insert(i + 1, (value / 2 + value % 2, depth + 1));
+        return depth + 1 > rules.explode_depth;
+    }
+
+    pub fn reduce(self) -> FlatSnailfishNumber {
+        return self.reduce_with(&PUZZLE_RULES);
+    }
+
+    // Elements sit one deeper than the pair holding them, so a pair nested `explode_depth` deep
+    // has elements at `explode_depth + 1`.
+    pub fn reduce_with(mut self, rules: &Rules) -> FlatSnailfishNumber {
+        // The leftmost two neighbours nested too deep at the same depth always make a pair.  The
+        // zero an explosion leaves behind can make a new pair with whatever is to its left, so
+        // look there again, but nothing further left can change.
+        let mut i = 0;
+        while i + 1 < self.elements.len() {
+            if self.elements[i].1 > rules.explode_depth && self.elements[i].1 == self.elements[i + 1].1 {
+                self.explode_at(i);
+                i = i.saturating_sub(1);
+            } else {
+                i += 1;
+            }
+        }
+        // After that, only a split can create a pair to explode, and it has to go immediately.
+        while let Some(i) = self.elements.iter().position(|(value, _)| *value >= rules.split_threshold as u64) {
+            if self.split_at(i, rules) {
+                self.explode_at(i);
+            }
+        }
+        return self;
+    }
+
+    pub fn magnitude(&self) -> u64 {
+        let mut stack: Vec<(u64, u8)> = Vec::new();
+        for (value, depth) in self.elements.iter() {
+            stack.push((*value, *depth));
+            while stack.len() >= 2 && stack[stack.len() - 1].1 == stack[stack.len() - 2].1 {
+                let (rhs, depth) = stack.pop().unwrap();
+                let (lhs, _) = stack.pop().unwrap();
+                stack.push((3 * lhs + 2 * rhs, depth - 1));
+            }
+        }
+        return stack.first().map(|(m, _)| *m).unwrap_or(0);
+    }
+}
@@ -0,0 +1,135 @@
+use std::env;
+use std::fs;
+use std::str::FromStr;
+
+mod snailfish;
+use snailfish::{PUZZLE_RULES, Rules, SnailfishNumber};
+
+const DAY: u8 = 18;
+
+// Homework, the rules to do it by, and the final sum.
+const EXAMPLES: [(&[&str], Rules, &str); 7] = [
+    (
+        &["[1,1]", "[2,2]", "[3,3]", "[4,4]"],
+        PUZZLE_RULES,
+        "[[[[1,1],[2,2]],[3,3]],[4,4]]"
+    ),
+    (
+        &["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]"],
+        PUZZLE_RULES,
+        "[[[[3,0],[5,3]],[4,4]],[5,5]]"
+    ),
+    (
+        &["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"],
+        PUZZLE_RULES,
+        "[[[[5,0],[7,4]],[5,5]],[6,6]]"
+    ),
+    (
+        &["[[[[4,3],4],4],[7,[[8,4],9]]]", "[1,1]"],
+        PUZZLE_RULES,
+        "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"
+    ),
+    (
+        &["[1,1]", "[2,2]"],
+        Rules { explode_depth: 1, split_threshold: 10 },
+        "[3,0]"
+    ),
+    (
+        &["[2,3]", "[1,1]"],
+        Rules { explode_depth: 4, split_threshold: 3 },
+        "[[2,[1,2]],[1,1]]"
+    ),
+    (
+        &["[[[[9,200],100],2],3]", "[1,1]"],
+        PUZZLE_RULES,
+        "[[[[6,7],[7,7]],[[7,7],[7,7]]],[[[7,8],[7,7]],[[0,8],[8,8]]]]"
+    )
+];
+
+// Arguments that don't make for rules, and why.
+const REJECTED: [(&[&str], &str); 5] = [
+    (&["--split-threshold", "0"], "Split threshold 0 would split forever, it must be at least 2"),
+    (&["--split-threshold", "1"], "Split threshold 1 would split forever, it must be at least 2"),
+    (&["--explode-depth", "0"], "Explode depth 0 is too shallow, it must be at least 1"),
+    (&["--split-threshold", "256"], "Bad value for --split-threshold: number too large to fit in target type"),
+    (&["--explode-depth"], "--explode-depth needs a value")
+];
+
+// Usage: homework [--explode-depth N] [--split-threshold N] [homework files...]
+// Defaults to the puzzle's rules and input.
+fn main() {
+    let results = EXAMPLES.iter()
+        .map(|(numbers, rules, expected)| (
+            numbers.join(" + "),
+            sum(numbers.iter().cloned(), rules)
+                .and_then(|actual| if *expected == actual.to_string() {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {} but got {}", expected, actual));
+                })
+        ))
+        .chain(REJECTED.iter().map(|(args, expected)| (
+            format!("[{}]", args.join(" ")),
+            match parse_args(args.iter().map(|arg| arg.to_string()).collect()) {
+                Ok(_) => Err(format!("Expected '{}' but the arguments were accepted", expected)),
+                Err(msg) => if *expected == msg {
+                    Ok(())
+                } else {
+                    Err(format!("Expected '{}' but got '{}'", expected, msg))
+                }
+            }
+        )))
+        .collect::<Vec<(String, Result<(), String>)>>();
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("Example {} passed.", name),
+            Err(msg) => println!("Example {} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("Please address errors before attempting any homework.")
+    }
+
+    let (rules, mut files) = parse_args(env::args().skip(1).collect()).expect("Bad arguments.");
+    if files.len() == 0 {
+        files.push(format!("{}/input", DAY));
+    }
+    for filename in files {
+        let result = fs::read_to_string(&filename)
+            .map_err(|io_error| format!("{}", io_error))
+            .and_then(|contents| sum(contents.lines(), &rules));
+        match result {
+            Ok(total) => println!("{}: {} (magnitude {})", filename, total, total.magnitude()),
+            Err(msg) => println!("{} failed: {}.", filename, msg)
+        }
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<(Rules, Vec<String>), String> {
+    let (mut explode_depth, mut split_threshold) = (PUZZLE_RULES.explode_depth, PUZZLE_RULES.split_threshold);
+    let mut files = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let setting = match arg.as_str() {
+            "--explode-depth" => &mut explode_depth,
+            "--split-threshold" => &mut split_threshold,
+            _ => {
+                files.push(arg);
+                continue;
+            }
+        };
+        *setting = args.next()
+            .ok_or(format!("{} needs a value", arg))?
+            .parse()
+            .map_err(|e| format!("Bad value for {}: {}", arg, e))?;
+    }
+    return Ok((Rules::new(explode_depth, split_threshold)?, files));
+}
+
+fn sum<'a, I: Iterator<Item = &'a str>>(numbers: I, rules: &Rules) -> Result<SnailfishNumber, String> {
+    let parsed = numbers
+        .map(SnailfishNumber::from_str)
+        .collect::<Result<Vec<SnailfishNumber>, String>>()?;
+    return SnailfishNumber::sum_with(parsed.into_iter(), rules)
+        .ok_or("Must have at least one Number".to_string());
+}
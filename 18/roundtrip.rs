@@ -0,0 +1,88 @@
+use std::fs;
+use std::str::FromStr;
+
+#[path = "../shared/random.rs"]
+mod random;
+use random::Random;
+mod snailfish;
+use snailfish::SnailfishNumber;
+
+const EXAMPLES: [&str; 1] = ["1"];
+
+const GENERATED: u64 = 500;
+
+const MALFORMED: [(&str, &str); 9] = [
+    ("", "Expected '[' or a digit at column 1 but the number ended"),
+    ("[1,2", "Expected ']' at column 5 but the number ended"),
+    ("[1 2]", "Expected ',' at column 3 but found ' '"),
+    ("[1,2]]", "Unexpected ']' after the number at column 6"),
+    ("[[1,2]", "Expected ',' at column 7 but the number ended"),
+    ("[1,[2,3,4]]", "Expected ']' at column 8 but found ','"),
+    ("[1, 2]", "Expected '[' or a digit at column 4 but found ' '"),
+    ("[a,2]", "Expected '[' or a digit at column 2 but found 'a'"),
+    ("[1,4294967296]", "Regular number 4294967296 at column 4 is too large")
+];
+
+const DAY: u8 = 18;
+
+fn main() {
+    let mut results = EXAMPLES.iter()
+        .map(|name| (
+            format!("Example {}", name),
+            fs::read_to_string(format!("{}/{}.ie", DAY, name))
+                .map_err(|io_error| format!("{}", io_error))
+                .and_then(|contents| contents.lines().map(round_trip).collect())
+        ))
+        .collect::<Vec<(String, Result<(), String>)>>();
+    results.extend(
+        (1..=GENERATED).map(|seed| (
+            format!("Generated number {}", seed),
+            round_trip(&generate(&mut Random::new(seed), 0))
+        ))
+    );
+    results.extend(
+        MALFORMED.iter().map(|(text, expected)| (
+            format!("Malformed '{}'", text),
+            match SnailfishNumber::from_str(text) {
+                Ok(n) => Err(format!("Expected an error but parsed {}", n)),
+                Err(actual) => if *expected == actual {
+                    Ok(())
+                } else {
+                    Err(format!("Expected '{}' but got '{}'", expected, actual))
+                }
+            }
+        ))
+    );
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("{} passed.", name),
+            Err(msg) => println!("{} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("The parser or display is broken.")
+    }
+}
+
+// Canonical text must parse, and display exactly as it was written.
+fn round_trip(text: &str) -> Result<(), String> {
+    let parsed = SnailfishNumber::from_str(text)?;
+    let displayed = parsed.to_string();
+    if displayed != text {
+        return Err(format!("{} displayed as {}", text, displayed));
+    }
+    if SnailfishNumber::from_str(&displayed)? != parsed {
+        return Err(format!("{} did not parse back to itself", displayed));
+    }
+    return Ok(());
+}
+
+// Not necessarily reduced: deeper than four pairs, with regular numbers as large as they come.
+fn generate(random: &mut Random, depth: u64) -> String {
+    if depth > 0 && random.below(8) < depth {
+        return random.below(1 << 32).to_string();
+    }
+    let lhs = generate(random, depth + 1);
+    let rhs = generate(random, depth + 1);
+    return format!("[{},{}]", lhs, rhs);
+}
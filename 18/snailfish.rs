@@ -0,0 +1,320 @@
+// Shared by every day 18 program, none of which uses all of it.
+#![allow(dead_code)]
+
+use std::str::FromStr;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Add;
+
+// How deep a pair has to be nested to explode, and how large a number has to be to split.
+#[derive(Clone, Copy)]
+pub struct Rules {
+    pub explode_depth: u8,
+    pub split_threshold: u8
+}
+
+pub const PUZZLE_RULES: Rules = Rules {
+    explode_depth: 4,
+    split_threshold: 10
+};
+
+impl Rules {
+    // Anything that splits below 2 splits into a pair that splits again, forever.  And a pair has
+    // to be nested in at least one other to have anywhere to explode into.
+    pub fn new(explode_depth: u8, split_threshold: u8) -> Result<Rules, String> {
+        if explode_depth < 1 {
+            return Err(format!("Explode depth {} is too shallow, it must be at least 1", explode_depth));
+        }
+        if split_threshold < 2 {
+            return Err(format!("Split threshold {} would split forever, it must be at least 2", split_threshold));
+        }
+        return Ok(Rules { explode_depth: explode_depth, split_threshold: split_threshold });
+    }
+}
+
+// Regular numbers are read as u32 but kept as u64.  Explosions only ever move values around or
+// throw them away, and splits keep their total, so no regular number can outgrow the total of
+// everything that went into a sum.
+#[derive(Clone, PartialEq, Eq)]
+pub enum SnailfishNumber {
+    Pair(Box<SnailfishNumber>, Box<SnailfishNumber>),
+    Single(u64)
+}
+
+// Strictly `[lhs,rhs]` or a regular number, with no whitespace.
+impl FromStr for SnailfishNumber {
+    type Err = String;
+    fn from_str(s: &str) -> Result<SnailfishNumber, String> {
+        let chars = s.chars().collect::<Vec<char>>();
+        let mut position = 0;
+        let number = parse_number(&chars, &mut position)?;
+        if let Some(c) = chars.get(position) {
+            return Err(format!("Unexpected '{}' after the number at column {}", c, position + 1));
+        }
+        return Ok(number);
+    }
+}
+
+fn parse_number(chars: &Vec<char>, position: &mut usize) -> Result<SnailfishNumber, String> {
+    return match chars.get(*position) {
+        Some('[') => {
+            *position += 1;
+            let lhs = parse_number(chars, position)?;
+            expect(chars, position, ',')?;
+            let rhs = parse_number(chars, position)?;
+            expect(chars, position, ']')?;
+            Ok(SnailfishNumber::Pair(Box::new(lhs), Box::new(rhs)))
+        },
+        Some(c) if c.is_digit(10) => {
+            let start = *position;
+            while chars.get(*position).map_or(false, |c| c.is_digit(10)) {
+                *position += 1;
+            }
+            let digits = chars[start..*position].iter().collect::<String>();
+            digits.parse::<u32>()
+                .map(|d| SnailfishNumber::Single(d as u64))
+                .map_err(|_| format!("Regular number {} at column {} is too large", digits, start + 1))
+        },
+        Some(c) => Err(format!("Expected '[' or a digit at column {} but found '{}'", *position + 1, c)),
+        None => Err(format!("Expected '[' or a digit at column {} but the number ended", *position + 1))
+    };
+}
+
+fn expect(chars: &Vec<char>, position: &mut usize, expected: char) -> Result<(), String> {
+    return match chars.get(*position) {
+        Some(c) if *c == expected => {
+            *position += 1;
+            Ok(())
+        },
+        Some(c) => Err(format!("Expected '{}' at column {} but found '{}'", expected, *position + 1, c)),
+        None => Err(format!("Expected '{}' at column {} but the number ended", expected, *position + 1))
+    };
+}
+
+impl fmt::Display for SnailfishNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            SnailfishNumber::Pair(lhs, rhs) => write!(f, "[{},{}]", lhs, rhs),
+            SnailfishNumber::Single(val) => write!(f, "{}", val)
+        }
+    }
+}
+
+// Snailfish addition always reduces, by the puzzle's rules.
+impl Add for SnailfishNumber {
+    type Output = SnailfishNumber;
+    fn add(self, rhs: SnailfishNumber) -> Self::Output {
+        return self.add_with(rhs, &PUZZLE_RULES);
+    }
+}
+
+// There is no empty snailfish number, so summing nothing gives None.
+impl Sum<SnailfishNumber> for Option<SnailfishNumber> {
+    fn sum<I: Iterator<Item = SnailfishNumber>>(iter: I) -> Option<SnailfishNumber> {
+        return SnailfishNumber::sum_with(iter, &PUZZLE_RULES);
+    }
+}
+
+impl<'a> Sum<&'a SnailfishNumber> for Option<SnailfishNumber> {
+    fn sum<I: Iterator<Item = &'a SnailfishNumber>>(iter: I) -> Option<SnailfishNumber> {
+        return SnailfishNumber::sum_with(iter.cloned(), &PUZZLE_RULES);
+    }
+}
+
+enum ExplodeResult {
+    Nothing(), //Nothing exploded
+    Done(), //SOmething exploded, but we're done.
+    Left(u64), //Explosion, remaining Leftward addition
+    Right(u64), //Explosion, remaining Rightward addition
+    Explosion(u64, u64) //Explosion
+}
+
+impl SnailfishNumber {
+    // Pairs two numbers without reducing them.
+    pub fn pair(lhs: SnailfishNumber, rhs: SnailfishNumber) -> SnailfishNumber {
+        return SnailfishNumber::Pair(Box::new(lhs), Box::new(rhs));
+    }
+    pub fn add_with(self, rhs: SnailfishNumber, rules: &Rules) -> SnailfishNumber {
+        return SnailfishNumber::pair(self, rhs).reduce_with(rules);
+    }
+    pub fn sum_with<I: Iterator<Item = SnailfishNumber>>(mut numbers: I, rules: &Rules) -> Option<SnailfishNumber> {
+        let first = numbers.next()?;
+        return Some(numbers.fold(first, |sum, next| sum.add_with(next, rules)));
+    }
+    fn explode(self, depth: u8, rules: &Rules) -> (ExplodeResult, SnailfishNumber) {
+        return match self {
+            SnailfishNumber::Single(n) => (ExplodeResult::Nothing(), SnailfishNumber::Single(n)),
+            SnailfishNumber::Pair(lhs, rhs) => match lhs.explode(depth + 1, rules) {
+                (ExplodeResult::Done(), sn) => (ExplodeResult::Done(), SnailfishNumber::pair(sn, *rhs)),
+                (ExplodeResult::Left(n), sn) => (ExplodeResult::Left(n), SnailfishNumber::pair(sn, *rhs)),
+                (ExplodeResult::Right(n), sn) => (ExplodeResult::Done(), SnailfishNumber::pair(sn, rhs.add_left(n))),
+                (ExplodeResult::Explosion(l, r), sn) => (ExplodeResult::Left(l), SnailfishNumber::pair(sn, rhs.add_left(r))),
+                (ExplodeResult::Nothing(), lhs) => match rhs.explode(depth + 1, rules) {
+                    (ExplodeResult::Done(), sn) => (ExplodeResult::Done(), SnailfishNumber::pair(lhs, sn)),
+                    (ExplodeResult::Left(n), sn) => (ExplodeResult::Done(), SnailfishNumber::pair(lhs.add_right(n), sn)),
+                    (ExplodeResult::Right(n), sn) => (ExplodeResult::Right(n), SnailfishNumber::pair(lhs, sn)),
+                    (ExplodeResult::Explosion(l, r), sn) => (ExplodeResult::Right(r), SnailfishNumber::pair(lhs.add_right(l), sn)),
+                    // Anything deep enough with a pair inside would have exploded that pair instead.
+                    (ExplodeResult::Nothing(), rhs) => match (lhs, rhs) {
+                        (SnailfishNumber::Single(l), SnailfishNumber::Single(r)) if depth >= rules.explode_depth => (
+                            ExplodeResult::Explosion(l, r),
+                            SnailfishNumber::Single(0)
+                        ),
+                        (lhs, rhs) => (ExplodeResult::Nothing(), SnailfishNumber::pair(lhs, rhs))
+                    }
+                }
+            }
+        }
+    }
+    fn split(self, rules: &Rules) -> (bool, SnailfishNumber) {
+        return match self {
+            SnailfishNumber::Single(d) => if d >= rules.split_threshold as u64 {
+                (true, SnailfishNumber::pair(SnailfishNumber::Single(d/2), SnailfishNumber::Single(d/2 + (d%2))))
+            } else {
+                (false, SnailfishNumber::Single(d))
+            },
+            SnailfishNumber::Pair(lhs, rhs) => match lhs.split(rules) {
+                (true, sn) => (true, SnailfishNumber::pair(sn, *rhs)),
+                (false, lhs) => match rhs.split(rules) {
+                    (b, rhs) => (b, SnailfishNumber::pair(lhs, rhs))
+                }
+            }
+        }
+    }
+    pub fn reduce(self) -> SnailfishNumber {
+        return self.reduce_with(&PUZZLE_RULES);
+    }
+    // A loop rather than recursion, since small thresholds can take a great many steps.
+    pub fn reduce_with(self, rules: &Rules) -> SnailfishNumber {
+        let mut current = self;
+        loop {
+            let (result, exploded) = current.explode(0, rules);
+            current = match result {
+                ExplodeResult::Nothing() => match exploded.split(rules) {
+                    (false, splitted) => return splitted,
+                    (true, splitted) => splitted
+                },
+                _ => exploded
+            };
+        }
+    }
+    // Reduces exactly as `reduce_with` does, recording every action taken along the way.
+    pub fn reduce_traced(self, rules: &Rules) -> (SnailfishNumber, Vec<Step>) {
+        let mut steps = Vec::new();
+        let mut current = self;
+        loop {
+            let before = current.clone();
+            let (result, exploded) = current.explode(0, rules);
+            let (action, after) = match result {
+                ExplodeResult::Nothing() => match exploded.split(rules) {
+                    (false, unchanged) => return (unchanged, steps),
+                    (true, splitted) => (Action::Split, splitted)
+                },
+                _ => (Action::Explode, exploded)
+            };
+            steps.push(Step::new(action, before, after.clone(), rules));
+            current = after;
+        }
+    }
+    // Every regular number, left to right, with its path from the root.
+    fn regulars(&self, path: String, found: &mut Vec<(String, u64)>) {
+        match self {
+            SnailfishNumber::Single(d) => found.push((path, *d)),
+            SnailfishNumber::Pair(lhs, rhs) => {
+                lhs.regulars(format!("{}L", path), found);
+                rhs.regulars(format!("{}R", path), found);
+            }
+        }
+    }
+    fn add_right(self, n: u64) -> SnailfishNumber {
+        return match self {
+            SnailfishNumber::Single(d) => SnailfishNumber::Single(d + n),
+            SnailfishNumber::Pair(lhs, rhs) => SnailfishNumber::pair(*lhs, rhs.add_right(n))
+        }
+    }
+    fn add_left(self, n: u64) -> SnailfishNumber {
+        return match self {
+            SnailfishNumber::Single(d) => SnailfishNumber::Single(d + n),
+            SnailfishNumber::Pair(lhs, rhs) => SnailfishNumber::pair(lhs.add_left(n), *rhs)
+        }
+    }
+    pub fn magnitude(&self) -> u64 {
+        return match self {
+            SnailfishNumber::Single(d) => *d,
+            SnailfishNumber::Pair(l, r) => 3 * l.magnitude() + 2 * r.magnitude()
+        }
+    }
+}
+
+pub enum Action {
+    Explode,
+    Split
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Action::Explode => write!(f, "explode"),
+            Action::Split => write!(f, "split")
+        }
+    }
+}
+
+pub struct Step {
+    pub action: Action,
+    // Path from the root to the pair that exploded or the number that split, e.g. "LLRL".
+    pub position: String,
+    pub before: SnailfishNumber,
+    pub after: SnailfishNumber,
+    // Regular numbers that received an exploded value, as (path, before, after).
+    pub receivers: Vec<(String, u64, u64)>
+}
+
+impl Step {
+    fn new(action: Action, before: SnailfishNumber, after: SnailfishNumber, rules: &Rules) -> Step {
+        let mut regulars = Vec::new();
+        before.regulars(String::new(), &mut regulars);
+        let mut receivers = Vec::new();
+        let position = match action {
+            // The leftmost pair of regular numbers nested deeply enough is the one to go.
+            Action::Explode => {
+                let i = (0..regulars.len() - 1)
+                    .find(|i| {
+                        let (l, r) = (&regulars[*i].0, &regulars[i + 1].0);
+                        l.len() > rules.explode_depth as usize && l.len() == r.len() && l[..l.len() - 1] == r[..r.len() - 1]
+                    })
+                    .expect("Explosion without a pair deep enough to explode.");
+                if i > 0 {
+                    let (path, value) = &regulars[i - 1];
+                    receivers.push((path.clone(), *value, value + regulars[i].1));
+                }
+                if let Some((path, value)) = regulars.get(i + 2) {
+                    receivers.push((path.clone(), *value, value + regulars[i + 1].1));
+                }
+                regulars[i].0[..regulars[i].0.len() - 1].to_string()
+            },
+            Action::Split => regulars.iter()
+                .find(|(_, value)| *value >= rules.split_threshold as u64)
+                .map(|(path, _)| path.clone())
+                .expect("Split without a number large enough to split.")
+        };
+        return Step {
+            action: action,
+            position: position,
+            before: before,
+            after: after,
+            receivers: receivers
+        };
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "after {}: {}", self.action, self.after)?;
+        write!(f, " ({} at {}", self.action, if self.position.len() == 0 { "root" } else { &self.position })?;
+        for (path, before, after) in self.receivers.iter() {
+            write!(f, "; {} at {} became {}", before, path, after)?;
+        }
+        return write!(f, ")");
+    }
+}
@@ -0,0 +1,100 @@
+use std::env;
+use std::fs;
+use std::str::FromStr;
+
+mod snailfish;
+use snailfish::{PUZZLE_RULES, SnailfishNumber, Step};
+
+// Numbers to add up, and the steps the puzzle says reducing their sum takes.
+const EXAMPLES: [(&[&str], &[&str]); 5] = [
+    (
+        &["[[[[4,3],4],4],[7,[[8,4],9]]]", "[1,1]"],
+        &[
+            "after explode: [[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+            "after explode: [[[[0,7],4],[15,[0,13]]],[1,1]]",
+            "after split: [[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+            "after split: [[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+            "after explode: [[[[0,7],4],[[7,8],[6,0]]],[8,1]]"
+        ]
+    ),
+    (&["[[[[[9,8],1],2],3],4]"], &["after explode: [[[[0,9],2],3],4]"]),
+    (&["[7,[6,[5,[4,[3,2]]]]]"], &["after explode: [7,[6,[5,[7,0]]]]"]),
+    (&["[[6,[5,[4,[3,2]]]],1]"], &["after explode: [[6,[5,[7,0]]],3]"]),
+    (
+        &["[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]"],
+        &[
+            "after explode: [[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            "after explode: [[3,[2,[8,0]]],[9,[5,[7,0]]]]"
+        ]
+    )
+];
+
+// Usage: trace [homework files...], tracing every addition in each file after checking the examples.
+fn main() {
+    let results = EXAMPLES.iter()
+        .map(|(numbers, expected)| (numbers.join(" + "), check(numbers, expected)))
+        .collect::<Vec<(String, Result<(), String>)>>();
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("Example {} passed.", name),
+            Err(msg) => println!("Example {} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("Please address errors before tracing anything.")
+    }
+
+    for filename in env::args().skip(1) {
+        println!("{}:", filename);
+        let traced = fs::read_to_string(&filename)
+            .map_err(|io_error| format!("{}", io_error))
+            .and_then(|contents| trace(&contents.lines().collect::<Vec<&str>>()));
+        match traced {
+            Ok((sum, additions)) => {
+                for (added, steps) in additions {
+                    println!("after addition: {}", added);
+                    steps.iter().for_each(|step| println!("{}", step));
+                }
+                println!("= {} (magnitude {})", sum, sum.magnitude());
+            },
+            Err(msg) => println!("Failed to trace {}: {}.", filename, msg)
+        }
+    }
+}
+
+fn check(numbers: &[&str], expected: &[&str]) -> Result<(), String> {
+    let (_, additions) = trace(numbers)?;
+    let steps = additions.iter()
+        .flat_map(|(_, steps)| steps.iter())
+        .collect::<Vec<&Step>>();
+    for (i, step) in steps.iter().enumerate() {
+        let actual = format!("after {}: {}", step.action, step.after);
+        match expected.get(i) {
+            Some(e) if *e == actual => (),
+            Some(e) => return Err(format!("Step {} should be {} but was {}", i + 1, e, step)),
+            None => return Err(format!("Unexpected step {}: {}", i + 1, step))
+        }
+    }
+    if steps.len() < expected.len() {
+        return Err(format!("Missing step {}: {}", steps.len() + 1, expected[steps.len()]));
+    }
+    return Ok(());
+}
+
+// The final sum, and each number as it was added along with the steps reducing it.
+fn trace(numbers: &[&str]) -> Result<(SnailfishNumber, Vec<(SnailfishNumber, Vec<Step>)>), String> {
+    let mut parsed = numbers.iter()
+        .map(|n| SnailfishNumber::from_str(n))
+        .collect::<Result<Vec<SnailfishNumber>, String>>()?
+        .into_iter();
+    let first = parsed.next().ok_or("Must have at least one Number".to_string())?;
+    let (mut sum, steps) = first.clone().reduce_traced(&PUZZLE_RULES);
+    let mut additions = vec![(first, steps)];
+    for next in parsed {
+        let added = SnailfishNumber::pair(sum, next);
+        let (reduced, steps) = added.clone().reduce_traced(&PUZZLE_RULES);
+        additions.push((added, steps));
+        sum = reduced;
+    }
+    return Ok((sum, additions));
+}
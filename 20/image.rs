@@ -0,0 +1,64 @@
+// Shared by every day 20 program, none of which uses all of it.
+#![allow(dead_code)]
+
+use automaton::{Automaton, Edges, Rule};
+
+// In reading order, so that the pixels spell out an index into the algorithm from the top bit down.
+const SQUARE: [(i64, i64); 9] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 0), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+// Every pixel becomes whatever the algorithm says for the 3x3 square around it, out to infinity.
+pub struct Enhancement {
+    algorithm: [bool; 512]
+}
+
+impl Rule for Enhancement {
+    type Cell = bool;
+
+    fn neighbourhood(&self) -> &[(i64, i64)] {
+        return &SQUARE;
+    }
+
+    fn edges(&self) -> Edges {
+        return Edges::Unbounded;
+    }
+
+    fn update(&self, _phase: usize, _lit: bool, square: &[bool]) -> bool {
+        return self.algorithm[square.iter().fold(0, |index, lit| index * 2 + *lit as usize)];
+    }
+
+    fn live(&self, lit: bool) -> bool {
+        return lit;
+    }
+}
+
+pub fn parse(contents: String) -> Result<Automaton<Enhancement>, String> {
+    let mut lines = contents.lines();
+    let algo = lines.next()
+        .ok_or(String::from("No lines in Input."))
+        .and_then(parse_algorithm)?;
+    assert_eq!(lines.next(), Some(""));
+    let pixels = lines
+        .map(|l| l.chars().map(parse_pixel).collect())
+        .collect::<Result<Vec<Vec<bool>>, String>>()?;
+    return Automaton::new(Enhancement { algorithm: algo }, pixels, false);
+}
+
+fn parse_algorithm(line: &str) -> Result<[bool; 512], String> {
+    let mut algo = [false; 512];
+    let mut chars = line.chars();
+    for i in 0..512 {
+        algo[i] = chars.next()
+            .ok_or(String::from("Not enough characters in line."))
+            .and_then(parse_pixel)?;
+    }
+
+    return Ok(algo)
+}
+
+fn parse_pixel(c: char) -> Result<bool, String> {
+    return match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err(format!("Unrecognized pixel value: '{}'", c.to_string())),
+    }
+}
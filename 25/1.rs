@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+use std::fs;
+
+#[path = "../shared/automaton.rs"]
+mod automaton;
+use automaton::{Automaton, Edges, Rule};
+
+type ParseTarget = SeaFloor;
+type Solution = usize;
+
+const EXAMPLES: [(&str, Solution); 2] = [
+    ("1", 58),
+    ("2", 1311)
+];
+
+// Sea floors the herds never stop moving on.
+const ENDLESS: [(&str, &str); 1] = [
+    ("3", "Goes round every 2 steps from step 0 without ever being done")
+];
+
+const DAY: u8 = 25;
+
+// Print the sea floor after every step.
+const RENDER_FRAMES: bool = false;
+
+fn main() {
+    let results = EXAMPLES.iter()
+        .zip(
+            EXAMPLES.iter()
+                .map(|t| format!("{}/{}.ie", DAY, t.0))
+                .map(operation)
+        )
+        .map(|((name, expected), result)| 
+            (
+                name.to_string(),
+                result
+                    .and_then(|actual| if *expected == actual {
+                        return Ok(());
+                    } else {
+                        return Err(format!("Expected {} but got {}", expected, actual));
+                    })
+            )
+        )
+        // The general automaton is far too slow for the input, but should agree on the examples.
+        .chain(EXAMPLES.iter().map(|(name, expected)| (
+            format!("{} (automaton)", name),
+            fs::read_to_string(format!("{}/{}.ie", DAY, name))
+                .map_err(|io_error| format!("{}", io_error))
+                .and_then(parse_herds)
+                .and_then(|mut herds| herds.run_until(|stats| stats.changed == 0))
+                .map(|stats| stats.step as usize)
+                .and_then(|actual| if *expected == actual {
+                    return Ok(());
+                } else {
+                    return Err(format!("Expected {} but got {}", expected, actual));
+                })
+        )))
+        .chain(ENDLESS.iter().map(|(name, expected)| (
+            format!("{} endless", name),
+            match operation(format!("{}/{}.ie", DAY, name)) {
+                Ok(actual) => Err(format!("Expected \"{}\" but stopped after {} steps", expected, actual)),
+                Err(msg) if msg == *expected => Ok(()),
+                Err(msg) => Err(format!("Expected \"{}\" but got \"{}\"", expected, msg))
+            }
+        )))
+        .chain(ENDLESS.iter().map(|(name, expected)| (
+            format!("{} endless (automaton)", name),
+            match fs::read_to_string(format!("{}/{}.ie", DAY, name))
+                .map_err(|io_error| format!("{}", io_error))
+                .and_then(parse_herds)
+                .and_then(|mut herds| herds.run_until(|stats| stats.changed == 0))
+            {
+                Ok(stats) => Err(format!("Expected \"{}\" but stopped after {} steps", expected, stats.step)),
+                Err(msg) if msg == *expected => Ok(()),
+                Err(msg) => Err(format!("Expected \"{}\" but got \"{}\"", expected, msg))
+            }
+        )))
+        .collect::<Vec<(String, Result<(), String>)>>();
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("Example {} passed.", name),
+            Err(msg) => println!("Example {} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("Please address errors before attempting the problem.")
+    }
+
+    println!(
+        "{}",
+        operation(format!("{}/input", DAY)).expect("Unexpected Error in main input.")
+    );
+}
+
+fn error<T>(msg: &str) -> Result<T, String> {
+    return Err(String::from(msg));
+}
+
+fn operation(filename: String) -> Result<Solution, String> {
+    return fs::read_to_string(filename)
+        .map_err(|io_error| format!("{}", io_error))
+        .and_then(parse)
+        .and_then(solve);
+}
+
+// One row of the sea floor, one bit per column, wrapping around at `width`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct BitRow {
+    width: usize,
+    words: Vec<u64>
+}
+
+impl BitRow {
+    fn new(width: usize) -> BitRow {
+        return BitRow {
+            width: width,
+            words: vec![0; (width + 63) / 64]
+        };
+    }
+
+    fn get(&self, column: usize) -> bool {
+        return self.words[column / 64] & (1 << (column % 64)) != 0;
+    }
+
+    fn set(&mut self, column: usize) {
+        self.words[column / 64] |= 1 << (column % 64);
+    }
+
+    fn is_empty(&self) -> bool {
+        return self.words.iter().all(|w| *w == 0);
+    }
+
+    fn combine(&self, other: &BitRow, f: fn(u64, u64) -> u64) -> BitRow {
+        return BitRow {
+            width: self.width,
+            words: self.words.iter()
+                .zip(other.words.iter())
+                .map(|(l, r)| f(*l, *r))
+                .collect()
+        };
+    }
+
+    fn or(&self, other: &BitRow) -> BitRow {
+        return self.combine(other, |l, r| l | r);
+    }
+
+    fn and_not(&self, other: &BitRow) -> BitRow {
+        return self.combine(other, |l, r| l & !r);
+    }
+
+    // Column c of the result holds column c + 1 of self.
+    fn from_east(&self) -> BitRow {
+        let last = self.words.len() - 1;
+        let mut words = self.words.iter().enumerate()
+            .map(|(i, w)| (w >> 1) | if i < last { self.words[i + 1] << 63 } else { 0 })
+            .collect::<Vec<u64>>();
+        if self.get(0) {
+            words[(self.width - 1) / 64] |= 1 << ((self.width - 1) % 64);
+        }
+        return BitRow { width: self.width, words: words };
+    }
+
+    // Column c of the result holds column c - 1 of self.
+    fn from_west(&self) -> BitRow {
+        let mut words = self.words.iter().enumerate()
+            .map(|(i, w)| (w << 1) | if i > 0 { self.words[i - 1] >> 63 } else { 0 })
+            .collect::<Vec<u64>>();
+        if self.width % 64 != 0 {
+            words[self.width / 64] &= (1 << (self.width % 64)) - 1;
+        }
+        if self.get(self.width - 1) {
+            words[0] |= 1;
+        }
+        return BitRow { width: self.width, words: words };
+    }
+}
+
+#[derive(Clone)]
+struct SeaFloor {
+    width: usize,
+    height: usize,
+    east: Vec<BitRow>,
+    south: Vec<BitRow>
+}
+
+impl SeaFloor {
+    // Moves both herds, and reports whether any sea cucumber moved.
+    fn step(&mut self) -> bool {
+        let occupied = self.occupied();
+        let east_movers = self.east.iter()
+            .zip(occupied.iter())
+            .map(|(herd, occ)| herd.and_not(&occ.from_east()))
+            .collect::<Vec<BitRow>>();
+        self.east = self.east.iter()
+            .zip(east_movers.iter())
+            .map(|(herd, movers)| herd.and_not(movers).or(&movers.from_west()))
+            .collect();
+
+        let occupied = self.occupied();
+        let south_movers = (0..self.height)
+            .map(|y| self.south[y].and_not(&occupied[(y + 1) % self.height]))
+            .collect::<Vec<BitRow>>();
+        self.south = (0..self.height)
+            .map(|y| self.south[y]
+                .and_not(&south_movers[y])
+                .or(&south_movers[(y + self.height - 1) % self.height])
+            ).collect();
+
+        return east_movers.iter().chain(south_movers.iter()).any(|r| !r.is_empty());
+    }
+
+    fn occupied(&self) -> Vec<BitRow> {
+        return self.east.iter()
+            .zip(self.south.iter())
+            .map(|(e, s)| e.or(s))
+            .collect();
+    }
+
+    fn render(&self) -> String {
+        let mut canvas = vec![vec!['.'; self.width]; self.height];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.east[y].get(x) {
+                    canvas[y][x] = '>';
+                } else if self.south[y].get(x) {
+                    canvas[y][x] = 'v';
+                }
+            }
+        }
+        return canvas.into_iter()
+            .map(|line| line.into_iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n");
+    }
+}
+
+// The same herds cell by cell: east-facing sea cucumbers move in the first phase, looking west and
+// east, and south-facing ones in the second, looking north and south.
+struct Herds;
+
+const ACROSS_THEN_DOWN: [(i64, i64); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+impl Rule for Herds {
+    type Cell = char;
+
+    fn neighbourhood(&self) -> &[(i64, i64)] {
+        return &ACROSS_THEN_DOWN;
+    }
+
+    fn edges(&self) -> Edges {
+        return Edges::Wrapping;
+    }
+
+    fn phases(&self) -> usize {
+        return 2;
+    }
+
+    fn update(&self, phase: usize, cell: char, neighbours: &[char]) -> char {
+        let (herd, behind, ahead) = if phase == 0 {
+            ('>', neighbours[0], neighbours[1])
+        } else {
+            ('v', neighbours[2], neighbours[3])
+        };
+        if cell == '.' && behind == herd {
+            return herd;
+        }
+        if cell == herd && ahead == '.' {
+            return '.';
+        }
+        return cell;
+    }
+}
+
+fn parse_herds(contents: String) -> Result<Automaton<Herds>, String> {
+    let rows = contents.lines()
+        .map(|line| line.chars().collect())
+        .collect::<Vec<Vec<char>>>();
+    if let Some(c) = rows.iter().flat_map(|row| row.iter()).find(|c| !">v.".contains(**c)) {
+        return Err(format!("Unrecognized character '{}'.", c));
+    }
+    return Automaton::new(Herds, rows, '.');
+}
+
+fn parse(contents: String) -> Result<ParseTarget, String> {
+    let lines = contents.lines().collect::<Vec<&str>>();
+    let width = lines.get(0).map(|l| l.len()).unwrap_or(0);
+    if width == 0 {
+        return error("Empty sea floor.");
+    }
+    let mut east = Vec::new();
+    let mut south = Vec::new();
+    for (y, line) in lines.iter().enumerate() {
+        if line.len() != width {
+            return Err(format!("Row {} has length {}, expected {}.", y, line.len(), width));
+        }
+        let mut east_row = BitRow::new(width);
+        let mut south_row = BitRow::new(width);
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                '>' => east_row.set(x),
+                'v' => south_row.set(x),
+                '.' => (),
+                _ => return Err(format!("Unrecognized character '{}' at ({}, {}).", c, x, y))
+            }
+        }
+        east.push(east_row);
+        south.push(south_row);
+    }
+    return Ok(SeaFloor {
+        width: width,
+        height: lines.len(),
+        east: east,
+        south: south
+    });
+}
+
+fn solve(parsed: ParseTarget) -> Result<Solution, String> {
+    let mut floor = parsed;
+    if RENDER_FRAMES {
+        println!("Initial state:\n{}\n", floor.render());
+    }
+    // Herds that get back to somewhere they've been will go round forever.
+    let mut seen = HashMap::new();
+    let mut step = 0;
+    loop {
+        if let Some(start) = seen.insert((floor.east.clone(), floor.south.clone()), step) {
+            return Err(format!(
+                "Goes round every {} steps from step {} without ever being done",
+                step - start,
+                start
+            ));
+        }
+        let moved = floor.step();
+        step += 1;
+        if RENDER_FRAMES {
+            println!("After {} steps:\n{}\n", step, floor.render());
+        }
+        if !moved {
+            return Ok(step);
+        }
+    }
+}
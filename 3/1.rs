@@ -1,6 +1,10 @@
 use std::fs;
 use std::collections::HashMap;
 
+#[path = "../shared/iters.rs"]
+mod iters;
+use iters::Iters;
+
 type ParseTarget = Vec<String>;
 type Solution = u32;
 
@@ -91,13 +95,7 @@ fn as_char_vec(string: String) -> Vec<String> {
 }
 
 fn get_mode(string: String) -> char {
-    let mut counts: HashMap<char, usize> = HashMap::new();
-    for c in string.chars() {
-        counts.insert(
-            c,
-            counts.get(&c).unwrap_or(&0) + 1
-        );
-    }
+    let counts: HashMap<char, usize> = string.chars().counts();
     return counts.into_iter()
         .fold((' ', 0), |acc, n| if n.1 > acc.1 {n} else {acc})
         .0;
@@ -2,6 +2,10 @@ use std::fs;
 use std::cmp;
 use std::collections::HashMap;
 
+#[path = "../shared/iters.rs"]
+mod iters;
+use iters::Iters;
+
 type ParseTarget = Vec<Line>;
 type Solution = usize;
 
@@ -119,13 +123,7 @@ fn parse(contents: String) -> Result<ParseTarget, String> {
 }
 
 fn solve(parsed: ParseTarget) -> Result<Solution, String> {
-    let mut points: HashMap<(i32, i32), i32> = HashMap::new();
-    for point in parsed.iter().flat_map(Line::points) {
-        points.insert(
-            point,
-            *points.get(&point).unwrap_or(&0) + 1
-        );
-    }
+    let points: HashMap<(i32, i32), i32> = parsed.iter().flat_map(Line::points).counts();
 
     return Ok(points.iter()
         .filter(|(_,v)| **v >= 2)
@@ -1,6 +1,10 @@
 use std::fs;
 use std::collections::HashMap;
 
+#[path = "../shared/iters.rs"]
+mod iters;
+use iters::{Iters, tally};
+
 type ParseTarget = HashMap<usize, i64>;
 type Solution = i64;
 
@@ -64,14 +68,7 @@ fn parse(contents: String) -> Result<ParseTarget, String> {
         .map(str::parse)
         .collect::<Result<Vec<usize>, _>>()
         .map_err(|e| format!("Error parsing: {}", e))?;
-    let mut to_return = HashMap::new();
-    for age in ages {
-        to_return.insert(
-            age,
-            to_return.get(&age).unwrap_or(&0) + 1
-        );
-    }
-    return Ok(to_return);
+    return Ok(ages.into_iter().counts());
 }
 
 fn solve(days: usize, parsed: ParseTarget) -> Result<Solution, String> {
@@ -91,10 +88,7 @@ fn next_day(curr: HashMap<usize, i64>) -> HashMap<usize, i64> {
         );
     }
     let spawns = curr.get(&0).unwrap_or(&0);
-    next.insert(
-        6,
-        next.get(&6).unwrap_or(&0) + spawns
-    );
+    tally(&mut next, 6, *spawns);
     next.insert(8, *spawns);
     return next;
 }
@@ -2,6 +2,10 @@ use std::fs;
 use std::collections::HashSet;
 use std::collections::HashMap;
 
+#[path = "../shared/iters.rs"]
+mod iters;
+use iters::Iters;
+
 type ParseTarget = Vec<Display>;
 type Solution = usize;
 
@@ -46,9 +50,9 @@ fn main() {
     );
 }
 
-fn error<T>(msg: &str) -> Result<T, String> {
-    return Err(String::from(msg));
-}
+//fn error<T>(msg: &str) -> Result<T, String> {
+//    return Err(String::from(msg));
+//}
 
 fn operation(filename: String) -> Result<Solution, String> {
     return fs::read_to_string(filename)
@@ -144,22 +148,20 @@ fn build_lookup(signals: &Vec<Digit>) -> Result<DigitLookup, String> {
     let fives = signals.iter()
         .filter(|s| s.wires.len() == 5)
         .map(|s| {
-            one(l5_lookups.iter()
+            l5_lookups.iter()
                 .filter(|(starter, to_remove, _)|
                     build_indicator(&base_sets, starter, to_remove).is_subset(&s.wires)
                 ).map(|(_, _, val)| (*val, &s.wires)) 
-                .collect()
-            )
+                .exactly_one()
         }).collect::<Result<SolvedSignals, String>>()?;
     let sixes = signals.iter()
         .filter(|s| s.wires.len() == 6)
         .map(|s| {
-            one(l6_lookups.iter()
+            l6_lookups.iter()
                 .filter(|(starter, to_remove, _)|
                     ! build_indicator(&base_sets, starter, to_remove).is_subset(&s.wires)
                 ).map(|(_, _, val)| (*val, &s.wires)) 
-                .collect()
-            )
+                .exactly_one()
         }).collect::<Result<SolvedSignals, String>>()?;
 
     return Ok(base_sets.into_iter().chain(fives).chain(sixes)
@@ -173,11 +175,10 @@ fn lookup_length_based_identities(signals: &Vec<Digit>)
 {
     return LENGTH_LOOKUPS.iter()
         .map(|(length, val)| {
-            let found =one(signals
+            let found = signals
                 .iter()
                 .filter(|s| s.wires.len() == *length)
-                .collect()
-            )?;
+                .exactly_one()?;
             return Ok((*val, &found.wires));
         }).collect::<Result<HashMap<usize, &HashSet<char>>, String>>();
 }
@@ -199,22 +200,12 @@ fn build_indicator(
         .collect();
 }
 
-fn one<T: Clone>(v: Vec<T>) -> Result<T, String> {
-    if v.len() == 0 {
-        return error("Expected exactly one item, but found none.");
-    } else if v.len() > 1 {
-        return error("Expected exactly one item, but found many");
-    }
-    return Ok(v[0].clone())
-}
-
-
 fn decode(nums: &Vec<Digit>, lookup: &DigitLookup) -> Result<usize, String> {
     let visual: String = nums.iter()
         .map(|digit| {
-            let result = one(lookup.iter()
+            let result = lookup.iter()
             .filter(|(display, _)| *display == digit.wires)
-            .collect())?;
+            .exactly_one()?;
             
             return Ok(result.1.to_string())
         }).collect::<Result<String, String>>()?;
@@ -0,0 +1,265 @@
+// Shared by every day 9 program, none of which uses all of it.
+#![allow(dead_code)]
+
+use std::convert::TryFrom;
+
+use iters::Iters;
+
+pub type HeightMap = Vec<Vec<usize>>;
+
+pub fn parse(contents: String) -> Result<HeightMap, String> {
+    return contents.lines()
+        .into_iter()
+        .map(str::chars)
+        .map(|row| row.map(|c| c.to_digit(10)
+            .map(usize::try_from)
+            .ok_or(format!("Illegal character: {}", c))?
+            .map_err(|e| format!("{}", e))
+        ))
+        .map(Iterator::collect)
+        .collect();
+}
+
+// Every cell below 9 in one basin or another, each basin numbered in the order its first cell
+// turns up reading the map, with its size and its low points (also in reading order).  Plateaus
+// can leave a basin without any low point.
+pub struct Basins {
+    pub labels: Vec<Vec<Option<usize>>>,
+    pub sizes: Vec<usize>,
+    pub low_points: Vec<Vec<(usize, usize)>>
+}
+
+// Union-find over cells, by size and halving paths on the way up.
+struct DisjointSets {
+    parents: Vec<usize>,
+    sizes: Vec<usize>
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> DisjointSets {
+        return DisjointSets { parents: (0..n).collect(), sizes: vec![1; n] };
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        return i;
+    }
+
+    fn union(&mut self, lhs: usize, rhs: usize) {
+        let (mut lhs, mut rhs) = (self.find(lhs), self.find(rhs));
+        if lhs == rhs {
+            return;
+        }
+        if self.sizes[lhs] < self.sizes[rhs] {
+            std::mem::swap(&mut lhs, &mut rhs);
+        }
+        self.parents[rhs] = lhs;
+        self.sizes[lhs] += self.sizes[rhs];
+    }
+}
+
+fn is_low_point(heights: &HeightMap, y: usize, x: usize) -> bool {
+    let height = heights[y][x];
+    return [(y.wrapping_sub(1), x), (y + 1, x), (y, x.wrapping_sub(1)), (y, x + 1)].iter()
+        .filter_map(|(ny, nx)| heights.get(*ny).and_then(|row| row.get(*nx)))
+        .all(|n| *n > height);
+}
+
+pub fn label(heights: &HeightMap) -> Result<Basins, String> {
+    let width = heights.get(0).map(|row| row.len()).unwrap_or(0);
+    if heights.iter().any(|row| row.len() != width) {
+        return Err(String::from("Height map is not rectangular"));
+    }
+    let mut sets = DisjointSets::new(heights.len() * width);
+    for (y, row) in heights.iter().enumerate() {
+        for (x, height) in row.iter().enumerate() {
+            if *height == 9 {
+                continue;
+            }
+            if x + 1 < width && row[x + 1] != 9 {
+                sets.union(y * width + x, y * width + x + 1);
+            }
+            if y + 1 < heights.len() && heights[y + 1][x] != 9 {
+                sets.union(y * width + x, (y + 1) * width + x);
+            }
+        }
+    }
+
+    let mut ids = vec![None; heights.len() * width];
+    let mut basins = Basins { labels: Vec::new(), sizes: Vec::new(), low_points: Vec::new() };
+    for (y, row) in heights.iter().enumerate() {
+        let mut labels = Vec::new();
+        for (x, height) in row.iter().enumerate() {
+            if *height == 9 {
+                labels.push(None);
+                continue;
+            }
+            let root = sets.find(y * width + x);
+            let id = match ids[root] {
+                Some(id) => id,
+                None => {
+                    ids[root] = Some(basins.sizes.len());
+                    basins.sizes.push(sets.sizes[root]);
+                    basins.low_points.push(Vec::new());
+                    basins.sizes.len() - 1
+                }
+            };
+            if is_low_point(heights, y, x) {
+                basins.low_points[id].push((y, x));
+            }
+            labels.push(Some(id));
+        }
+        basins.labels.push(labels);
+    }
+    return Ok(basins);
+}
+
+const LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Background colours from the 256-colour palette, far enough apart to tell neighbours apart.
+const COLOURS: [u8; 12] = [160, 28, 26, 172, 91, 30, 124, 64, 61, 136, 127, 37];
+
+// Every basin in a colour of its own (as far as the colours go), with its heights written over
+// it.  Without colour each basin is a letter instead, and the 9s between them are dots.
+pub fn render(heights: &HeightMap, basins: &Basins, colour: bool) -> String {
+    return heights.iter()
+        .zip(basins.labels.iter())
+        .map(|(row, labels)| row.iter()
+            .zip(labels.iter())
+            .map(|(height, label)| match (label, colour) {
+                (Some(id), true) => format!("\x1b[48;5;{}m{}\x1b[0m", COLOURS[id % COLOURS.len()], height),
+                (Some(id), false) => LETTERS.chars().nth(id % LETTERS.len()).unwrap_or('?').to_string(),
+                (None, true) => height.to_string(),
+                (None, false) => String::from(".")
+            })
+            .collect::<String>()
+        )
+        .collect::<Vec<String>>()
+        .join("\n");
+}
+
+type Chunks = Vec<(usize, usize)>;
+struct Basin {
+    chunks: Chunks,
+    leading_edge: Chunks
+}
+
+impl Basin {
+    fn new(chunk: (usize, usize)) -> Basin {
+        return Basin {
+            chunks: Vec::new(),
+            leading_edge: vec!(chunk)
+        };
+    }
+
+    fn empty() -> Basin {
+        return Basin {
+            chunks: Vec::new(),
+            leading_edge: Vec::new()
+        }
+    }
+
+    fn extend(&mut self, chunks: Chunks) -> Chunks {
+        if self.leading_edge.len() == 0 {
+            return chunks;
+        }
+        let (new_edge, rest): (Chunks, Chunks) = chunks.iter()
+            .partition(|t| self.matches(t));
+        self.chunks.append(&mut self.leading_edge);
+        self.leading_edge = new_edge;
+        return rest;
+    }
+
+    fn matches(&self, (candidate_start, candidate_end): &(usize, usize)) -> bool {
+         return self.leading_edge.iter()
+             .any(|(edge_start, edge_end)|
+                 (edge_start <= candidate_start && candidate_start <= edge_end)
+                 || (edge_start <= candidate_end && candidate_end <= edge_end)
+                 || (candidate_start <= edge_start && edge_end <= candidate_end)
+              )
+    }
+
+    fn close(&mut self) {
+        self.chunks.append(&mut self.leading_edge);
+        self.leading_edge = Vec::new();
+    }
+    fn combine(&mut self, that: &mut Basin) {
+        self.chunks.append(&mut that.chunks);
+        self.leading_edge.append(&mut that.leading_edge);
+    }
+
+    fn size(&self) -> usize {
+        return self.chunks.iter()
+            .map(|(s, e)| e - s + 1)
+            .sum()
+    }
+}
+
+// The sizes of every basin, the old way: each row split into runs between 9s, and runs that
+// overlap the runs of a basin in the row above joining that basin.
+pub fn interval_sizes(heights: &HeightMap) -> Vec<usize> {
+    let intervals = heights.iter()
+        .map(|v| [9].iter()
+            .chain(v)
+            .chain([&9])
+            .cloned()
+            .collect::<Vec<usize>>())
+        .map(to_intervals);
+    let mut basins: Vec<Basin> = Vec::new();
+
+    for row in intervals {
+        let multi_matching_chunks: Chunks = row.iter()
+            .filter(|t| basins.iter().filter(|b| b.matches(t)).count() > 1)
+            .cloned()
+            .collect();
+        //Combine any basins that match multiple chunks - they were secretly the same basin.
+        for t in multi_matching_chunks {
+            let (to_combine, rest): (Vec<Basin>, Vec<Basin>) = basins.into_iter()
+                .partition(|b| b.matches(&t));
+            let mut combined = Basin::empty();
+            for mut b in to_combine {
+                combined.combine(&mut b);
+            }
+
+            // Doing this iteratively means "W" structures will be properly combined.
+            // (See 3.ie)
+            basins = rest;
+            basins.push(combined);
+        }
+
+        //Combine chunks with the (now combined) basins they matched.
+        let remaining = basins.iter_mut().fold(row, |r, basin| basin.extend(r));
+
+        // All remaining chunks are NEW basins.
+        for chunk in remaining {
+            basins.push(Basin::new(chunk));
+        }
+    }
+
+    for basin in basins.iter_mut() {
+        basin.close();
+    }
+
+    return basins.iter().map(Basin::size).collect();
+}
+
+fn to_intervals(measurements: Vec<usize>) -> Vec<(usize, usize)> {
+    return measurements.into_iter()
+        .pairwise()
+        .enumerate()
+        .flat_map(|(idx, pair)| match pair {
+            (9, 9) => None,
+            (9, _) => Some(idx),
+            (_, 9) => Some(idx - 1),
+            _ => None
+        }).collect::<Vec<usize>>()
+        .chunks(2)
+        .map(|s| match s {
+            [lhs, rhs] => (*lhs, *rhs),
+            _ => panic!("Should have been guaranteed doubles.")
+        })
+        .collect();
+}
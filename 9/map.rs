@@ -0,0 +1,185 @@
+use std::env;
+use std::fs;
+
+#[path = "../shared/iters.rs"]
+mod iters;
+#[path = "../shared/random.rs"]
+mod random;
+use random::Random;
+mod basins;
+use basins::{Basins, HeightMap, interval_sizes, label, parse, render};
+
+const DAY: u8 = 9;
+
+// Height maps, the size of each basin in them, each basin's low points, and how they render
+// without colour.
+const EXAMPLES: [(&str, &[usize], &[&[(usize, usize)]], &str); 3] = [
+    (
+        "1",
+        &[3, 9, 14, 9],
+        &[&[(0, 1)], &[(0, 9)], &[(2, 2)], &[(4, 6)]],
+        "aa...bbbbb\na.ccc.b.bb\n.ccccc.d.b\nccccc.ddd.\n.c...ddddd"
+    ),
+    ("2", &[7, 1, 1], &[&[(0, 3)], &[(0, 7)], &[(2, 7)]], "aa.a...b\naaaa....\n.......c"),
+    (
+        "3",
+        &[11, 1, 1],
+        &[&[(0, 0), (0, 3), (0, 6)], &[(0, 10)], &[(2, 10)]],
+        "a.aaa.a...b\naaa.aaa....\n..........c"
+    )
+];
+
+// Height maps that aren't, and why.
+const MALFORMED: [(&str, &str); 2] = [
+    ("12\n1", "Height map is not rectangular"),
+    ("1x", "Illegal character: x")
+];
+
+// Seeds to generate height maps from, how many, how big, and what percentage of cells are 9s.
+// Every one of them should have the same basins by union-find as by intervals.
+const GENERATED: [(u64, usize, usize, usize, u64); 4] = [
+    (1, 200, 10, 10, 30),
+    (2, 100, 30, 40, 40),
+    (3, 50, 50, 50, 20),
+    (4, 200, 5, 60, 45)
+];
+
+// Usage: map [--plain] [height map files...]
+// Draws each map with its basins coloured in, then lists every basin's size and low points,
+// biggest first.  `--plain` draws letters instead of colours.  Defaults to the puzzle input.
+fn main() {
+    let results = EXAMPLES.iter()
+        .map(|(name, sizes, low_points, rendered)| (
+            name.to_string(),
+            fs::read_to_string(format!("{}/{}.ie", DAY, name))
+                .map_err(|io_error| format!("{}", io_error))
+                .and_then(parse)
+                .and_then(|heights| check(&heights).map(|basins| (heights, basins)))
+                .and_then(|(heights, basins)| {
+                    if basins.sizes != *sizes {
+                        return Err(format!("Expected sizes {:?} but got {:?}", sizes, basins.sizes));
+                    }
+                    if basins.low_points.iter().map(|points| &points[..]).collect::<Vec<&[(usize, usize)]>>() != *low_points {
+                        return Err(format!("Expected low points {:?} but got {:?}", low_points, basins.low_points));
+                    }
+                    let actual = render(&heights, &basins, false);
+                    if actual != *rendered {
+                        return Err(format!("Expected\n{}\nbut got\n{}\n", rendered, actual));
+                    }
+                    return Ok(());
+                })
+        ))
+        .chain(MALFORMED.iter().map(|(contents, expected)| (
+            format!("{:?}", contents),
+            match parse(contents.to_string()).and_then(|heights| label(&heights)) {
+                Ok(basins) => Err(format!("Expected '{}' but found basins {:?}", expected, basins.sizes)),
+                Err(msg) => if *expected == msg {
+                    Ok(())
+                } else {
+                    Err(format!("Expected '{}' but got '{}'", expected, msg))
+                }
+            }
+        )))
+        .chain(GENERATED.iter().map(|(seed, maps, height, width, walls)| (
+            format!("generated {} ({} {}x{} maps, {}% 9s)", seed, maps, height, width, walls),
+            cross_check(*seed, *maps, *height, *width, *walls)
+        )))
+        .collect::<Vec<(String, Result<(), String>)>>();
+    results.iter()
+        .for_each(|(name, result)| match result {
+            Ok(()) => println!("Example {} passed.", name),
+            Err(msg) => println!("Example {} failed: {}.", name, msg)
+        });
+
+    if results.iter().any(|t| t.1.is_err()) {
+        panic!("Please address errors before mapping any basins.")
+    }
+
+    let mut args = env::args().skip(1).collect::<Vec<String>>();
+    let colour = !args.iter().any(|arg| arg == "--plain");
+    args.retain(|arg| arg != "--plain");
+    if args.len() == 0 {
+        args.push(format!("{}/input", DAY));
+    }
+    for filename in args.iter() {
+        let result = fs::read_to_string(filename)
+            .map_err(|io_error| format!("{}", io_error))
+            .and_then(parse)
+            .and_then(|heights| label(&heights).map(|basins| describe(&heights, &basins, colour)));
+        match result {
+            Ok(description) => println!("{}:\n{}", filename, description),
+            Err(msg) => println!("{} failed: {}.", filename, msg)
+        }
+    }
+}
+
+fn describe(heights: &HeightMap, basins: &Basins, colour: bool) -> String {
+    let mut biggest = (0..basins.sizes.len()).collect::<Vec<usize>>();
+    biggest.sort_by_key(|id| std::cmp::Reverse(basins.sizes[*id]));
+    let listed = biggest.iter()
+        .map(|id| format!("basin {}: size {}, low points {:?}", id, basins.sizes[*id], basins.low_points[*id]))
+        .collect::<Vec<String>>();
+    return format!("{}\n{}", render(heights, basins, colour), listed.join("\n"));
+}
+
+// Labels the basins, making sure the labels agree with the sizes and low points: every cell
+// below 9 has one, neighbours below 9 share theirs, and every low point is in its own basin.
+fn check(heights: &HeightMap) -> Result<Basins, String> {
+    let basins = label(heights)?;
+    let mut counted = vec![0; basins.sizes.len()];
+    for (y, row) in heights.iter().enumerate() {
+        for (x, height) in row.iter().enumerate() {
+            let id = basins.labels[y][x];
+            if (*height == 9) != id.is_none() {
+                return Err(format!("({}, {}) is {} but labelled {:?}", y, x, height, id));
+            }
+            if let Some(id) = id {
+                counted[id] += 1;
+            }
+            let right = row.get(x + 1).map(|h| (*h, basins.labels[y][x + 1]));
+            let below = heights.get(y + 1).map(|r| (r[x], basins.labels[y + 1][x]));
+            for (neighbour, neighbour_id) in right.into_iter().chain(below.into_iter()) {
+                if *height != 9 && neighbour != 9 && neighbour_id != id {
+                    return Err(format!("({}, {}) is next to a different basin", y, x));
+                }
+            }
+        }
+    }
+    if counted != basins.sizes {
+        return Err(format!("Sizes are {:?} but the labels count {:?}", basins.sizes, counted));
+    }
+    for (id, points) in basins.low_points.iter().enumerate() {
+        if let Some((y, x)) = points.iter().find(|(y, x)| basins.labels[*y][*x] != Some(id)) {
+            return Err(format!("Low point ({}, {}) is outside basin {}", y, x, id));
+        }
+    }
+    return Ok(basins);
+}
+
+fn cross_check(seed: u64, maps: usize, height: usize, width: usize, walls: u64) -> Result<(), String> {
+    let mut random = Random::new(seed);
+    for _ in 0..maps {
+        let heights = (0..height)
+            .map(|_| (0..width)
+                .map(|_| if random.below(100) < walls { 9 } else { random.below(9) as usize })
+                .collect()
+            )
+            .collect::<HeightMap>();
+        let mut found = check(&heights)?.sizes;
+        let mut expected = interval_sizes(&heights);
+        found.sort();
+        expected.sort();
+        if found != expected {
+            return Err(format!(
+                "Intervals found basins of {:?} but union-find found {:?} in\n{}\n",
+                expected,
+                found,
+                heights.iter()
+                    .map(|row| row.iter().map(|h| h.to_string()).collect::<String>())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ));
+        }
+    }
+    return Ok(());
+}
@@ -0,0 +1,286 @@
+// Shared by programs across days, none of which uses all of it.  Pull it in with
+// `#[path = "../shared/automaton.rs"] mod automaton;` and `use automaton::{Automaton, Rule};`.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// What lies past the edges of the grid.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Edges {
+    // Nothing.  Neighbours out there read as the outside cell, and cascades never reach them.
+    Bounded,
+    // The grid again - off the right is back on the left, off the bottom back on the top.
+    Wrapping,
+    // More cells, every one of them the outside cell.  The grid grows each step by as far as the
+    // neighbourhood reaches, and the outside cell changes as one surrounded by its like would.
+    Unbounded
+}
+
+// How cells change from one step to the next.  A step runs through every phase, each of them
+// updating every cell at once from how things stood after the one before.  Then any cell past the
+// threshold fires, cascading into its neighbours, which may take them past the threshold too.
+// Nothing fires twice in a step, and whatever fired settles once the cascade is over.
+pub trait Rule {
+    type Cell: Copy + Eq + Hash;
+
+    // Where a cell's neighbours are, as (dy, dx).
+    fn neighbourhood(&self) -> &[(i64, i64)];
+
+    fn edges(&self) -> Edges;
+
+    fn phases(&self) -> usize {
+        return 1;
+    }
+
+    // What a cell becomes in a phase, given its neighbours in neighbourhood order.
+    fn update(&self, phase: usize, cell: Self::Cell, neighbours: &[Self::Cell]) -> Self::Cell;
+
+    fn fires(&self, _cell: Self::Cell) -> bool {
+        return false;
+    }
+
+    // What a neighbour of a cell that fired becomes.
+    fn cascade(&self, cell: Self::Cell) -> Self::Cell {
+        return cell;
+    }
+
+    // What a cell that fired becomes once the cascade is over.
+    fn settle(&self, cell: Self::Cell) -> Self::Cell {
+        return cell;
+    }
+
+    // Whether a cell counts as alive, for the statistics.
+    fn live(&self, _cell: Self::Cell) -> bool {
+        return false;
+    }
+}
+
+// What happened in a step: how many cells fired, how many ended up different from how they
+// started, and how many are alive afterwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub step: u64,
+    pub fired: usize,
+    pub changed: usize,
+    pub live: usize
+}
+
+// Where a grid starts going round: the step it first got back to, and how many steps it takes to
+// get back there.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cycle {
+    pub start: u64,
+    pub length: u64
+}
+
+pub struct Automaton<R: Rule> {
+    pub rule: R,
+    pub height: usize,
+    pub width: usize,
+    pub cells: Vec<R::Cell>,
+    pub outside: R::Cell,
+    pub steps: u64
+}
+
+impl<R: Rule> Automaton<R> {
+    pub fn new(rule: R, rows: Vec<Vec<R::Cell>>, outside: R::Cell) -> Result<Automaton<R>, String> {
+        let width = rows.get(0).map(|row| row.len()).unwrap_or(0);
+        if width == 0 {
+            return Err(String::from("Empty grid"));
+        }
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(String::from("Grid is not rectangular"));
+        }
+        return Ok(Automaton {
+            rule: rule,
+            height: rows.len(),
+            width: width,
+            cells: rows.into_iter().flat_map(|row| row.into_iter()).collect(),
+            outside: outside,
+            steps: 0
+        });
+    }
+
+    // Where (y, x) is kept, if it's in the grid at all.
+    fn index(&self, y: i64, x: i64) -> Option<usize> {
+        let (h, w) = (self.height as i64, self.width as i64);
+        let (y, x) = match self.rule.edges() {
+            Edges::Wrapping => (y.rem_euclid(h), x.rem_euclid(w)),
+            _ if y < 0 || x < 0 || y >= h || x >= w => return None,
+            _ => (y, x)
+        };
+        return Some((y * w + x) as usize);
+    }
+
+    pub fn get(&self, y: i64, x: i64) -> R::Cell {
+        return self.index(y, x).map(|i| self.cells[i]).unwrap_or(self.outside);
+    }
+
+    // Surrounds the grid with `margin` more outside cells on every side.
+    fn grow(&mut self, margin: usize) {
+        let width = self.width + 2 * margin;
+        let mut cells = vec![self.outside; (self.height + 2 * margin) * width];
+        for (i, cell) in self.cells.iter().enumerate() {
+            cells[(i / self.width + margin) * width + i % self.width + margin] = *cell;
+        }
+        self.height += 2 * margin;
+        self.width = width;
+        self.cells = cells;
+    }
+
+    pub fn step(&mut self) -> Stats {
+        let (before, before_width, before_outside) = (self.cells.clone(), self.width, self.outside);
+        let offsets = self.rule.neighbourhood().to_vec();
+        let margin = match self.rule.edges() {
+            Edges::Unbounded => offsets.iter().map(|(dy, dx)| dy.abs().max(dx.abs())).max().unwrap_or(0) as usize,
+            _ => 0
+        };
+        self.grow(margin);
+
+        let mut neighbours = Vec::with_capacity(offsets.len());
+        for phase in 0..self.rule.phases() {
+            let mut cells = Vec::with_capacity(self.cells.len());
+            for (i, cell) in self.cells.iter().enumerate() {
+                let (y, x) = ((i / self.width) as i64, (i % self.width) as i64);
+                neighbours.clear();
+                neighbours.extend(offsets.iter().map(|(dy, dx)| self.get(y + dy, x + dx)));
+                cells.push(self.rule.update(phase, *cell, &neighbours));
+            }
+            self.cells = cells;
+            if let Edges::Unbounded = self.rule.edges() {
+                self.outside = self.rule.update(phase, self.outside, &vec![self.outside; offsets.len()]);
+            }
+        }
+
+        let mut fired = self.cells.iter().map(|cell| self.rule.fires(*cell)).collect::<Vec<bool>>();
+        let mut queue = (0..self.cells.len()).filter(|i| fired[*i]).collect::<Vec<usize>>();
+        while let Some(i) = queue.pop() {
+            let (y, x) = ((i / self.width) as i64, (i % self.width) as i64);
+            for (dy, dx) in offsets.iter() {
+                if let Some(j) = self.index(y + dy, x + dx) {
+                    self.cells[j] = self.rule.cascade(self.cells[j]);
+                    if !fired[j] && self.rule.fires(self.cells[j]) {
+                        fired[j] = true;
+                        queue.push(j);
+                    }
+                }
+            }
+        }
+        for (i, fired) in fired.iter().enumerate() {
+            if *fired {
+                self.cells[i] = self.rule.settle(self.cells[i]);
+            }
+        }
+
+        self.steps += 1;
+        let changed = self.cells.iter()
+            .enumerate()
+            .filter(|(i, cell)| {
+                let (y, x) = (i / self.width, i % self.width);
+                let was = if y < margin || x < margin || y >= self.height - margin || x >= self.width - margin {
+                    before_outside
+                } else {
+                    before[(y - margin) * before_width + x - margin]
+                };
+                return **cell != was;
+            })
+            .count();
+        return Stats {
+            step: self.steps,
+            fired: fired.iter().filter(|f| **f).count(),
+            changed: changed,
+            live: self.cells.iter().filter(|cell| self.rule.live(**cell)).count()
+        };
+    }
+
+    pub fn run(&mut self, steps: u64) -> Vec<Stats> {
+        return (0..steps).map(|_| self.step()).collect();
+    }
+
+    // Everything that decides what happens next.
+    fn state(&self) -> (Vec<R::Cell>, R::Cell) {
+        return (self.cells.clone(), self.outside);
+    }
+
+    // Steps until the statistics for one satisfy `done`, and returns those.  A grid that gets back
+    // to somewhere it's been without being done never will be, so that's an error.  Unbounded
+    // grids grow every step and never get back anywhere - it's up to `done` to stop those.
+    pub fn run_until<P: FnMut(&Stats) -> bool>(&mut self, mut done: P) -> Result<Stats, String> {
+        let mut seen = HashMap::new();
+        loop {
+            if self.rule.edges() != Edges::Unbounded {
+                if let Some(start) = seen.insert(self.state(), self.steps) {
+                    return Err(format!(
+                        "Goes round every {} steps from step {} without ever being done",
+                        self.steps - start,
+                        start
+                    ));
+                }
+            }
+            let stats = self.step();
+            if done(&stats) {
+                return Ok(stats);
+            }
+        }
+    }
+
+    // Steps until the grid gets back to somewhere it's been.
+    pub fn find_cycle(&mut self) -> Result<Cycle, String> {
+        if self.rule.edges() == Edges::Unbounded {
+            return Err(String::from("Unbounded grids never repeat"));
+        }
+        let mut seen = HashMap::new();
+        loop {
+            if let Some(start) = seen.insert(self.state(), self.steps) {
+                return Ok(Cycle { start: start, length: self.steps - start });
+            }
+            self.step();
+        }
+    }
+
+    // Gets to step `target` and returns how many cells fired on the way, going round any cycle
+    // as many times as it takes in one go rather than step by step.
+    pub fn fast_forward(&mut self, target: u64) -> Result<u64, String> {
+        if target < self.steps {
+            return Err(format!("Already past step {}", target));
+        }
+        let first = self.steps;
+        let mut seen = HashMap::new();
+        let mut states = Vec::new();
+        // How many had fired by each step since the first.
+        let mut fired: Vec<u64> = vec![0];
+        while self.steps < target {
+            if self.rule.edges() != Edges::Unbounded {
+                let state = self.state();
+                if let Some(start) = seen.insert(state.clone(), self.steps - first) {
+                    let now = (self.steps - first) as usize;
+                    let (start, length) = (start as usize, now - start as usize);
+                    let (laps, left) = ((target - self.steps) / length as u64, (target - self.steps) % length as u64);
+                    let end = start + left as usize;
+                    let total = (fired[now] - fired[start]).checked_mul(laps)
+                        .and_then(|laps| laps.checked_add(fired[now]))
+                        .and_then(|total| total.checked_add(fired[end] - fired[start]))
+                        .ok_or("Too many cells fired to count")?;
+                    let (cells, outside) = states.swap_remove(end);
+                    self.cells = cells;
+                    self.outside = outside;
+                    self.steps = target;
+                    return Ok(total);
+                }
+                states.push(state);
+            }
+            let stats = self.step();
+            let total = fired[fired.len() - 1] + stats.fired as u64;
+            fired.push(total);
+        }
+        return Ok(fired[fired.len() - 1]);
+    }
+
+    pub fn render<F: Fn(R::Cell) -> char>(&self, draw: F) -> String {
+        return self.cells.chunks(self.width)
+            .map(|row| row.iter().map(|cell| draw(*cell)).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n");
+    }
+}
@@ -0,0 +1,103 @@
+// Shared by programs across days, none of which uses all of it.  Pull it in with
+// `#[path = "../shared/iters.rs"] mod iters;` and `use iters::Iters;`.
+#![allow(dead_code)]
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+pub trait Iters: Iterator + Sized {
+    // Every element alongside the next one: `[1, 2, 3]` gives `(1, 2), (2, 3)`.
+    fn pairwise(self) -> Pairwise<Self> {
+        return Pairwise { prev: None, backer: self };
+    }
+
+    // Every element alongside the next one if there is one: `[1, 2, 3]` gives
+    // `(1, Some(2)), (2, Some(3)), (3, None)`.
+    fn with_next(self) -> WithNext<Self> {
+        return WithNext { prev: None, backer: self };
+    }
+
+    // Every run of `n` consecutive elements, oldest first, holding on to no more than `n` of them.
+    fn windowed(self, n: usize) -> Windowed<Self> {
+        if n == 0 {
+            panic!("Windows must hold at least one element.");
+        }
+        return Windowed { n: n, window: VecDeque::with_capacity(n), backer: self };
+    }
+
+    // How many times each element appears, in whatever type the caller is counting with.
+    fn counts<N>(self) -> HashMap<Self::Item, N>
+    where Self::Item: Hash + Eq, N: Copy + Add<Output = N> + From<u8> {
+        let mut counts = HashMap::new();
+        for item in self {
+            tally(&mut counts, item, N::from(1));
+        }
+        return counts;
+    }
+
+    // The only element, or an error if there are none or several.
+    fn exactly_one(mut self) -> Result<Self::Item, String> {
+        let first = self.next().ok_or("Expected exactly one item, but found none.")?;
+        if self.next().is_some() {
+            return Err(String::from("Expected exactly one item, but found many"));
+        }
+        return Ok(first);
+    }
+}
+
+impl<I: Iterator> Iters for I {}
+
+// Adds `n` to the count for `key`, starting from zero.
+pub fn tally<K: Hash + Eq, N: Copy + Add<Output = N> + From<u8>>(counts: &mut HashMap<K, N>, key: K, n: N) {
+    let count = counts.entry(key).or_insert(N::from(0));
+    *count = *count + n;
+}
+
+pub struct Pairwise<I: Iterator> {
+    prev: Option<I::Item>,
+    backer: I
+}
+
+impl<I: Iterator> Iterator for Pairwise<I> where I::Item: Clone {
+    type Item = (I::Item, I::Item);
+    fn next(&mut self) -> Option<(I::Item, I::Item)> {
+        let first = self.prev.take().or_else(|| self.backer.next())?;
+        let second = self.backer.next()?;
+        self.prev = Some(second.clone());
+        return Some((first, second));
+    }
+}
+
+pub struct WithNext<I: Iterator> {
+    prev: Option<I::Item>,
+    backer: I
+}
+
+impl<I: Iterator> Iterator for WithNext<I> where I::Item: Clone {
+    type Item = (I::Item, Option<I::Item>);
+    fn next(&mut self) -> Option<(I::Item, Option<I::Item>)> {
+        let first = self.prev.take().or_else(|| self.backer.next())?;
+        self.prev = self.backer.next();
+        return Some((first, self.prev.clone()));
+    }
+}
+
+pub struct Windowed<I: Iterator> {
+    n: usize,
+    window: VecDeque<I::Item>,
+    backer: I
+}
+
+impl<I: Iterator> Iterator for Windowed<I> where I::Item: Clone {
+    type Item = Vec<I::Item>;
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        if self.window.len() == self.n {
+            self.window.pop_front();
+        }
+        while self.window.len() < self.n {
+            self.window.push_back(self.backer.next()?);
+        }
+        return Some(self.window.iter().cloned().collect());
+    }
+}
@@ -0,0 +1,26 @@
+// Shared by programs across days, none of which uses all of it.  Pull it in with
+// `#[path = "../shared/random.rs"] mod random;` and `use random::Random;`.
+#![allow(dead_code)]
+
+// xorshift64 - good enough for making up test input without pulling in a crate.  The same seed
+// always gives the same numbers, so any failure it turns up can be run again.
+pub struct Random {
+    state: u64
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        return Random { state: seed.wrapping_mul(0x9E3779B97F4A7C15) | 1 };
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        return self.state;
+    }
+
+    pub fn below(&mut self, n: u64) -> u64 {
+        return self.next() % n;
+    }
+}